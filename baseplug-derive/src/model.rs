@@ -49,8 +49,18 @@ struct ParameterInfo {
     short_name: Option<String>,
     label: Option<String>,
     unit: Option<String>,
+    unit_suffix: Option<String>,
     gradient: Option<String>,
-    dsp_notify: Option<String>
+    curve: Option<String>,
+    stored: Option<String>,
+    dsp_notify: Option<String>,
+    notify_threshold: Option<String>,
+    index: Option<usize>,
+
+    inverted: bool,
+    no_automation: bool,
+    momentary: bool,
+    switch: bool
 }
 
 struct FieldInfo<'a> {
@@ -58,10 +68,17 @@ struct FieldInfo<'a> {
     ident: &'a Ident,
     ty: &'a Type,
 
+    // attributes forwarded untouched onto the model struct's field.
+    attrs: Vec<&'a Attribute>,
+
+    // `#[cfg]` attributes, which go onto everything generated from the field.
+    cfgs: Vec<&'a Attribute>,
+
     wrapping: Option<WrappingType>,
 
     bounds: ModelBounds,
     smooth_ms: f32,
+    smooth_in_unit: bool,
 
     parameter_info: Option<ParameterInfo>
 }
@@ -78,6 +95,9 @@ impl<'a> FieldInfo<'a> {
             ident,
             ty,
 
+            attrs: Vec::new(),
+            cfgs: Vec::new(),
+
             wrapping: match &f.ty {
                 Type::Path(ref p) => Some(WrappingType::for_type(&p.path)),
                 _ => None
@@ -85,33 +105,39 @@ impl<'a> FieldInfo<'a> {
 
             bounds: ModelBounds::default(),
             smooth_ms: 5.0f32,
+            smooth_in_unit: false,
 
             parameter_info: None
         };
 
         for attr in f.attrs.iter() {
-            let meta = attr.parse_meta();
+            let path = &attr.path;
 
-            let (ident, nested) = match meta {
-                Ok(Meta::List(ref list)) => {
-                    (list.path.get_ident().unwrap(), &list.nested)
-                },
+            if path.is_ident("unsmoothed") {
+                info.wrapping = None;
+                continue;
+            }
 
-                Ok(Meta::Path(ref path)) => {
-                    if path.is_ident("unsmoothed") {
-                        info.wrapping = None;
-                    }
+            if path.is_ident("cfg") {
+                info.cfgs.push(attr);
+                continue;
+            }
 
-                    continue
-                },
+            if !path.is_ident("model") && !path.is_ident("parameter") {
+                // anything we don't know about (serde, docs, ...) belongs to the model struct.
+                info.attrs.push(attr);
+                continue;
+            }
 
-                _ => continue,
+            let list = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list,
+                _ => panic!("expected a list for attribute {}", path.get_ident().unwrap())
             };
 
-            match &*ident.to_string() {
-                "model" => info.populate_model_attrs(nested),
-                "parameter" => info.populate_parameter_attrs(nested),
-                ident => panic!("unexpected attribute {}", ident)
+            if path.is_ident("model") {
+                info.populate_model_attrs(&list.nested);
+            } else {
+                info.populate_parameter_attrs(&list.nested);
             }
         }
 
@@ -128,33 +154,60 @@ impl<'a> FieldInfo<'a> {
         let mut short_name = None;
         let mut label = None;
         let mut unit = None;
+        let mut unit_suffix = None;
         let mut gradient = None;
+        let mut curve = None;
+        let mut stored = None;
         let mut dsp_notify = None;
+        let mut notify_threshold = None;
+        let mut index = None;
 
+        let mut inverted = false;
+        let mut no_automation = false;
+        let mut momentary = false;
+        let mut switch = false;
+
+        // `key = "value"` pairs come through with `Some(value)`, bare flags with `None`.
         nested.iter()
             .filter_map(|attr| {
                 match attr {
                     NestedMeta::Meta(Meta::NameValue(MetaNameValue { path, lit, .. })) => {
                         let lit = match lit {
                             Lit::Str(s) => s.value(),
+                            Lit::Int(i) => i.base10_digits().to_string(),
                             _ => return None
                         };
 
                         path.get_ident()
-                            .map(|ident| (ident, lit))
+                            .map(|ident| (ident, Some(lit)))
                     },
 
+                    NestedMeta::Meta(Meta::Path(path)) =>
+                        path.get_ident()
+                            .map(|ident| (ident, None)),
+
                     _ => None
                 }
             })
         .for_each(|(ident, lit)| {
             match (&*ident.to_string(), lit) {
-                ("name", s) => name = Some(s),
-                ("short_name", s) => short_name = Some(s),
-                ("label", s) => label = Some(s),
-                ("unit", s) => unit = Some(s),
-                ("gradient", s) => gradient = Some(s),
-                ("dsp_notify", s) => dsp_notify = Some(s),
+                ("name", Some(s)) => name = Some(s),
+                ("short_name", Some(s)) => short_name = Some(s),
+                ("label", Some(s)) => label = Some(s),
+                ("unit", Some(s)) => unit = Some(s),
+                ("unit_suffix", Some(s)) => unit_suffix = Some(s),
+                ("gradient", Some(s)) => gradient = Some(s),
+                ("curve", Some(s)) => curve = Some(s),
+                ("stored", Some(s)) => stored = Some(s),
+                ("dsp_notify", Some(s)) => dsp_notify = Some(s),
+                ("notify_threshold", Some(s)) => notify_threshold = Some(s),
+                ("index", Some(s)) => index = Some(s.parse()
+                    .expect("\"index\" must be a non-negative integer")),
+
+                ("inverted", None) => inverted = true,
+                ("no_automation", None) => no_automation = true,
+                ("momentary", None) => momentary = true,
+                ("switch", None) => switch = true,
 
                 (ident, _) => panic!("unexpected attribute \"{}\"", ident)
             }
@@ -162,13 +215,35 @@ impl<'a> FieldInfo<'a> {
 
         let name = name.expect("\"name\" is a required parameter field");
 
+        if gradient.is_some() && curve.is_some() {
+            panic!("\"gradient\" and \"curve\" are mutually exclusive");
+        }
+
+        if switch && (gradient.is_some() || curve.is_some()) {
+            panic!("a \"switch\" has no \"gradient\" or \"curve\"");
+        }
+
+        if unit.is_some() && unit_suffix.is_some() {
+            panic!("\"unit\" and \"unit_suffix\" are mutually exclusive");
+        }
+
         self.parameter_info = Some(ParameterInfo {
             name,
             short_name,
             label,
             unit,
+            unit_suffix,
             gradient,
-            dsp_notify
+            curve,
+            stored,
+            dsp_notify,
+            notify_threshold,
+            index,
+
+            inverted,
+            no_automation,
+            momentary,
+            switch
         });
     }
 
@@ -188,12 +263,39 @@ impl<'a> FieldInfo<'a> {
                 ("min", Lit::Float(f)) => self.bounds.min = f.base10_parse().unwrap(),
                 ("max", Lit::Float(f)) => self.bounds.max = f.base10_parse().unwrap(),
                 ("smooth_ms", Lit::Float(f)) => self.smooth_ms = f.base10_parse().unwrap(),
+                ("smooth_in", Lit::Str(s)) => self.smooth_in_unit = match &*s.value() {
+                    "dsp" => false,
+                    "unit" => true,
+                    s => panic!("unexpected value for \"smooth_in\": \"{}\" (expected \"dsp\" or \"unit\")", s)
+                },
                 _ => ()
             }
         });
     }
 
-    fn parameter_repr(&self, model: &Ident) -> Option<TokenStream> {
+    // with `smooth_in = "unit"`, a decibel parameter that's stored as a gain coefficient ramps in
+    // decibels and is converted back per sample. for everything else the unit and DSP values
+    // are one and the same, so there's nothing to do.
+    fn smooth_domain(&self) -> Option<TokenStream> {
+        let param = self.parameter_info.as_ref()?;
+
+        if !self.smooth_in_unit || param.stored.as_deref() == Some("unit") {
+            return None;
+        }
+
+        match param.unit.as_deref() {
+            Some("Decibels") => Some(quote!(
+                ::baseplug::SmoothDomain {
+                    into: ::baseplug::util::coeff_to_db,
+                    from: ::baseplug::util::db_to_coeff
+                }
+            )),
+
+            _ => None
+        }
+    }
+
+    fn parameter_repr(&self, model: &Ident, index: TokenStream) -> Option<TokenStream> {
         let param = match self.parameter_info {
             Some(ref p) => p,
             None => return None
@@ -214,83 +316,167 @@ impl<'a> FieldInfo<'a> {
                 quote!(Some(#dn))
             });
 
-        let unit = param.unit.as_ref()
-            .map_or_else(
-                || quote!(Generic),
-                |u| TokenStream::from_str(u).unwrap());
+        let notify_threshold = param.notify_threshold.as_ref()
+            .map_or_else(|| quote!(None), |nt| {
+                let nt: f32 = nt.parse()
+                    .expect("\"notify_threshold\" must be a number");
+                quote!(Some(#nt))
+            });
+
+        let unit = match (&param.unit, &param.unit_suffix) {
+            (_, Some(suffix)) => quote!(Custom(#suffix)),
+            (Some(u), None) => TokenStream::from_str(u).unwrap(),
+            (None, None) => quote!(Generic)
+        };
 
         let param_type = {
             let min = self.bounds.min;
             let max = self.bounds.max;
 
-            let gradient = param.gradient.as_ref()
-                .map_or_else(
-                    || quote!(Linear),
-                    |l| TokenStream::from_str(l).unwrap());
+            // `curve` names a module providing `from_normalised()` and `to_normalised()`.
+            let gradient = match param.curve {
+                Some(ref c) => {
+                    let curve = TokenStream::from_str(c).unwrap();
 
-            quote!(
-                ::baseplug::parameter::Type::Numeric {
-                    min: #min,
-                    max: #max,
+                    quote!(Custom {
+                        from_normalised: #curve::from_normalised,
+                        to_normalised: #curve::to_normalised
+                    })
+                },
 
-                    gradient: ::baseplug::parameter::Gradient::#gradient
-                }
-            )
+                None => param.gradient.as_ref()
+                    .map_or_else(
+                        || quote!(Linear),
+                        |l| TokenStream::from_str(l).unwrap())
+            };
+
+            if param.switch {
+                quote!(
+                    ::baseplug::parameter::Type::Switch {
+                        min: #min,
+                        max: #max
+                    }
+                )
+            } else {
+                quote!(
+                    ::baseplug::parameter::Type::Numeric {
+                        min: #min,
+                        max: #max,
+
+                        gradient: ::baseplug::parameter::Gradient::#gradient
+                    }
+                )
+            }
+        };
+
+        let stored_as_unit = match param.stored.as_ref().map(|x| x.as_str()) {
+            None | Some("dsp") => false,
+            Some("unit") => true,
+            Some(s) => panic!("unexpected value for \"stored\": \"{}\" (expected \"dsp\" or \"unit\")", s)
+        };
+
+        let storage = if stored_as_unit {
+            quote!(Unit)
+        } else {
+            quote!(Dsp)
         };
 
+        let inverted = param.inverted;
+        let automatable = !param.no_automation;
+        let momentary = param.momentary;
+
         let model_get = match self.wrapping {
             None => quote!(model.#ident),
             _ => quote!(model.#ident.dest())
         };
 
         let display_cb = match param.unit.as_ref().map(|x| x.as_str()) {
-            Some("Decibels") => quote!(
-                |param: &#pty, model: &#model, w: &mut ::std::io::Write| ->
+            Some("Decibels") if stored_as_unit => quote!(
+                |param: &#pty, val: f32, w: &mut ::std::io::Write| ->
                         ::std::io::Result<()> {
-                    let val = #model_get;
-
-                    if val <= 0.00003162278 {
+                    if val <= -90.0 {
                         write!(w, "-inf")
                     } else {
-                        write!(w, "{:.1}", ::baseplug::util::coeff_to_db(val))
+                        write!(w, "{:.1}", val)
                     }
                 }
             ),
 
-            _ => quote!(
-                |param: &#pty, model: &#model, w: &mut ::std::io::Write| ->
+            Some("Decibels") => quote!(
+                |param: &#pty, val: f32, w: &mut ::std::io::Write| ->
                         ::std::io::Result<()> {
-                    write!(w, "{}", #model_get)
+                    if val <= 0.00003162278 {
+                        write!(w, "-inf")
+                    } else {
+                        write!(w, "{:.1}", ::baseplug::util::coeff_to_db(val))
+                    }
                 }
             ),
+
+            _ => match param.unit_suffix {
+                Some(ref suffix) => quote!(
+                    |param: &#pty, val: f32, w: &mut ::std::io::Write| ->
+                            ::std::io::Result<()> {
+                        write!(w, "{}{}", val, #suffix)
+                    }
+                ),
+
+                None => quote!(
+                    |param: &#pty, val: f32, w: &mut ::std::io::Write| ->
+                            ::std::io::Result<()> {
+                        write!(w, "{}", val)
+                    }
+                )
+            }
         };
 
         let set_cb = match self.wrapping {
             None => quote!(
                 |param: &#pty, model: &mut #model, val: f32| {
-                    model.#ident = val.xlate_from(param);
+                    model.#ident = ::baseplug::parameter::TranslateFrom::xlate_from(val, param);
                 }
             ),
 
             _ => quote!(
                 |param: &#pty, model: &mut #model, val: f32| {
-                    model.#ident.set(val.xlate_from(param))
+                    model.#ident.set(::baseplug::parameter::TranslateFrom::xlate_from(val, param))
                 }
             )
         };
 
         let get_cb = quote!(
             |param: &#pty, model: &#model| -> f32 {
-                #model_get.xlate_out(param)
+                ::baseplug::parameter::Translatable::xlate_out(&#model_get, param)
             }
         );
 
+        let value_cb = quote!(
+            |model: &#model| -> f32 {
+                #model_get
+            }
+        );
+
+        let status_cb = match self.wrapping {
+            None => quote!(
+                |_: &#model| ::baseplug::SmoothStatus::Inactive
+            ),
+
+            _ => quote!(
+                |model: &#model| model.#ident.status()
+            )
+        };
+
         Some(quote!(
             ::baseplug::Param {
                 name: #name,
                 short_name: #short_name,
+                index: #index,
 
                 unit: ::baseplug::parameter::Unit::#unit,
+                storage: ::baseplug::parameter::Storage::#storage,
+                inverted: #inverted,
+                automatable: #automatable,
+                momentary: #momentary,
 
                 param_type: #param_type,
                 format: ::baseplug::parameter::Format {
@@ -299,14 +485,92 @@ impl<'a> FieldInfo<'a> {
                 },
 
                 dsp_notify: #dsp_notify,
+                notify_threshold: #notify_threshold,
 
                 set_cb: #set_cb,
-                get_cb: #get_cb
+                get_cb: #get_cb,
+                value_cb: #value_cb,
+                status_cb: #status_cb
             }
         ))
     }
 }
 
+// `#[cfg]` on a model field has to gate everything generated from it, or the generated code would
+// refer to a field that doesn't exist. items that are `None` are skipped.
+fn cfg_gated<'a, T>(fields: &'a [FieldInfo], items: impl Iterator<Item = T> + 'a)
+    -> impl Iterator<Item = TokenStream> + 'a
+    where T: Into<Option<TokenStream>>
+{
+    fields.iter()
+        .zip(items)
+        .filter_map(|(field, item)| {
+            let cfgs = &field.cfgs;
+            item.into().map(|item| quote!(#( #cfgs )* #item))
+        })
+}
+
+// parameters pinned with `index = N` go to that host index, the rest fill the remaining slots in
+// field order. pinned indices have to leave no holes, since the host sees a dense list.
+fn parameters_in_index_order(fields: &[FieldInfo], model: &Ident) -> Vec<TokenStream> {
+    let params: Vec<_> = fields.iter()
+        .filter(|field| field.parameter_info.is_some())
+        .collect();
+
+    let mut slots: Vec<Option<&FieldInfo>> = vec![None; params.len()];
+
+    for field in params.iter() {
+        if let Some(idx) = field.parameter_info.as_ref().unwrap().index {
+            if idx >= slots.len() {
+                panic!("\"{}\": index {} is out of range, there are only {} parameters",
+                    field.ident, idx, slots.len());
+            }
+
+            if slots[idx].is_some() {
+                panic!("\"{}\": index {} is already taken", field.ident, idx);
+            }
+
+            slots[idx] = Some(field);
+        }
+    }
+
+    let mut unpinned = params.iter()
+        .filter(|field| field.parameter_info.as_ref().unwrap().index.is_none());
+
+    let ordered: Vec<&FieldInfo> = slots.into_iter()
+        .map(|slot| slot.or_else(|| unpinned.next().copied()).unwrap())
+        .collect();
+
+    ordered.iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let earlier = &ordered[..i];
+
+            // the position in `PARAMS`, which doesn't count earlier parameters that are cfg'd out.
+            let index = if earlier.iter().all(|f| f.cfgs.is_empty()) {
+                quote!(#i)
+            } else {
+                let counted = earlier.iter()
+                    .map(|f| {
+                        let cfgs = &f.cfgs;
+                        quote!(#( #cfgs )* { index += 1; })
+                    });
+
+                quote!({
+                    let mut index = 0usize;
+                    #( #counted )*
+                    index
+                })
+            };
+
+            let cfgs = &field.cfgs;
+            let repr = field.parameter_repr(model, index).unwrap();
+
+            quote!(#( #cfgs )* & #repr)
+        })
+        .collect()
+}
+
 pub(crate) fn derive(input: DeriveInput) -> TokenStream {
     let attrs = &input.attrs;
     let model_vis = &input.vis;
@@ -325,11 +589,11 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
         .collect();
 
     let model_fields = fields_base.iter()
-        .map(|FieldInfo { vis, ident, ty, .. }| {
-            quote!(#vis #ident: #ty)
+        .map(|FieldInfo { vis, ident, ty, attrs, cfgs, .. }| {
+            quote!(#( #cfgs )* #( #attrs )* #vis #ident: #ty)
         });
 
-    let smoothed_fields = fields_base.iter()
+    let smoothed_fields = cfg_gated(&fields_base, fields_base.iter()
         .map(|FieldInfo { vis, ident, wrapping, ty, .. }| {
             match wrapping {
                 Some(wrap_type) => {
@@ -339,24 +603,28 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
 
                 None => quote!(#vis #ident: #ty)
             }
-        });
+        }));
+
+    // doc comments are the only forwarded attributes that also make sense on the process fields.
+    let proc_fields = cfg_gated(&fields_base, fields_base.iter()
+        .map(|FieldInfo { vis, ident, wrapping, ty, attrs, .. }| {
+            let docs = attrs.iter()
+                .filter(|attr| attr.path.is_ident("doc"));
 
-    let proc_fields = fields_base.iter()
-        .map(|FieldInfo { vis, ident, wrapping, ty, .. }| {
             match wrapping {
                 Some(WrappingType::Smooth) =>
-                    quote!(#vis #ident:
+                    quote!(#( #docs )* #vis #ident:
                         ::baseplug::SmoothOutput<'proc, #ty>),
 
                 Some(WrappingType::Declick) =>
-                    quote!(#vis #ident:
+                    quote!(#( #docs )* #vis #ident:
                         ::baseplug::DeclickOutput<'proc, #ty>),
 
-                None => quote!(#vis #ident: &'proc #ty)
+                None => quote!(#( #docs )* #vis #ident: &'proc #ty)
             }
-        });
+        }));
 
-    let get_process_fields = fields_base.iter()
+    let get_process_fields = cfg_gated(&fields_base, fields_base.iter()
         .map(|FieldInfo { ident, wrapping, .. }| {
             match wrapping {
                 Some(WrappingType::Smooth) =>
@@ -365,7 +633,9 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
 
                         ::baseplug::SmoothOutput {
                             values: &out.values[..nframes],
-                            status: out.status
+                            status: out.status,
+                            dest: out.dest,
+                            changed: out.changed
                         }
                     }),
 
@@ -383,9 +653,9 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
 
                 None => quote!(#ident: &self.#ident)
             }
-        });
+        }));
 
-    let current_value_fields = fields_base.iter()
+    let current_value_fields = cfg_gated(&fields_base, fields_base.iter()
         .map(|FieldInfo { ident, wrapping, .. }| {
             match wrapping {
                 Some(WrappingType::Smooth) =>
@@ -394,7 +664,9 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
 
                         ::baseplug::SmoothOutput {
                             values: out.values,
-                            status: out.status
+                            status: out.status,
+                            dest: out.dest,
+                            changed: out.changed
                         }
                     }),
 
@@ -412,54 +684,96 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
 
                 None => quote!(#ident: &self.#ident)
             }
-        });
+        }));
 
-    let set_statements = fields_base.iter()
+    let set_statements = cfg_gated(&fields_base, fields_base.iter()
         .map(|FieldInfo { ident, wrapping, .. }| {
             match wrapping {
                 Some(WrappingType::Smooth) =>
                     quote!(self.#ident.set(from.#ident)),
                 Some(WrappingType::Declick) =>
                     quote!(self.#ident.set(from.#ident.clone())),
-                None => quote!(self.#ident = from.#ident)
-            }
-        });
 
-    let from_model_fields = fields_base.iter()
-        .map(|FieldInfo { ident, wrapping, .. }| {
-            match wrapping {
-                Some(WrappingType::Smooth) =>
+                // in a block, since `#[cfg]` on a bare assignment isn't stable.
+                None => quote!({ self.#ident = from.#ident })
+            }
+        }));
+
+    let from_model_fields = cfg_gated(&fields_base, fields_base.iter()
+        .map(|field @ FieldInfo { ident, wrapping, .. }| {
+            match (wrapping, field.smooth_domain()) {
+                (Some(WrappingType::Smooth), Some(domain)) =>
+                    quote!(#ident: ::baseplug::Smooth::with_domain(model.#ident, #domain)),
+                (Some(WrappingType::Smooth), None) =>
                     quote!(#ident: ::baseplug::Smooth::new(model.#ident)),
-                Some(WrappingType::Declick) =>
+                (Some(WrappingType::Declick), _) =>
                     quote!(#ident: ::baseplug::Declick::new(model.#ident)),
-                None => quote!(#ident: model.#ident)
+                (None, _) => quote!(#ident: model.#ident)
             }
-        });
+        }));
 
-    let reset_statements = fields_base.iter()
+    let reset_statements = cfg_gated(&fields_base, fields_base.iter()
         .map(|FieldInfo { ident, wrapping, .. }| {
             match wrapping {
                 Some(WrappingType::Smooth) =>
                     quote!(self.#ident.reset(from.#ident)),
                 Some(WrappingType::Declick) =>
                     quote!(self.#ident.reset(from.#ident.clone())),
-                None => quote!(self.#ident = from.#ident)
+                None => quote!({ self.#ident = from.#ident })
             }
-        });
+        }));
 
-    let process_statements = fields_base.iter()
+    let process_statements = cfg_gated(&fields_base, fields_base.iter()
         .map(|FieldInfo { ident, wrapping, .. }| {
+            // settle (or promote) anything that finished during the last block before
+            // rendering this one. nothing else ever takes a `Smooth` out of `Active`, so without
+            // this a parameter that has moved once would report `is_smoothing()` forever.
             wrapping.as_ref().map(|_|
-                quote!(self.#ident.process(nframes)))
-        });
+                quote!({
+                    self.#ident.update_status();
+                    self.#ident.process(nframes)
+                }))
+        }));
 
-    let set_sample_rate_statements = fields_base.iter()
+    let smoothing_checks = cfg_gated(&fields_base, fields_base.iter()
+        .map(|FieldInfo { ident, wrapping, .. }| {
+            wrapping.as_ref().map(|_|
+                quote!({
+                    if self.#ident.status != ::baseplug::SmoothStatus::Inactive {
+                        return true;
+                    }
+                }))
+        }));
+
+    let changed_checks = cfg_gated(&fields_base, fields_base.iter()
+        .map(|FieldInfo { ident, wrapping, .. }| {
+            match wrapping {
+                Some(WrappingType::Smooth) =>
+                    Some(quote!({
+                        if self.#ident.changed() {
+                            return true;
+                        }
+                    })),
+
+                // a declick is only ever active while it's crossfading.
+                Some(WrappingType::Declick) =>
+                    Some(quote!({
+                        if self.#ident.status == ::baseplug::SmoothStatus::Active {
+                            return true;
+                        }
+                    })),
+
+                None => None
+            }
+        }));
+
+    let set_sample_rate_statements = cfg_gated(&fields_base, fields_base.iter()
         .map(|FieldInfo { ident, wrapping, smooth_ms, .. }| {
             wrapping.as_ref().map(|_|
                 quote!(self.#ident.set_speed_ms(sample_rate, #smooth_ms)))
-        });
+        }));
 
-    let as_model_fields = fields_base.iter()
+    let as_model_fields = cfg_gated(&fields_base, fields_base.iter()
         .map(|FieldInfo { ident, wrapping, .. }| {
             match wrapping {
                 Some(WrappingType::Smooth) => quote!(#ident: self.#ident.dest()),
@@ -467,16 +781,14 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
                     quote!(#ident: self.#ident.dest().clone()),
                 None => quote!(#ident: self.#ident)
             }
-        });
+        }));
 
     let smoothed_ident = format_ident!("{}Smooth", model_name);
     let proc_ident = format_ident!("{}Process", model_name);
 
-    let impl_params = format_ident!("_IMPL_PARAMETERS_FOR_{}", model_name);
+    let parameters = parameters_in_index_order(&fields_base, &smoothed_ident);
 
-    let parameters = fields_base.iter()
-        .filter_map(|field: &FieldInfo|
-            field.parameter_repr(&smoothed_ident));
+    let proc_doc = format!("the values of a [`{}`] for one `process()` call.", model_name);
 
     quote!(
         #( #attrs )*
@@ -489,8 +801,28 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
             #( #smoothed_fields ),*
         }
 
+        #[doc = #proc_doc]
         #model_vis struct #proc_ident<'proc> {
-            #( #proc_fields ),*
+            #( #proc_fields, )*
+
+            // keeps `'proc` in use even if the model has no fields.
+            __proc: ::std::marker::PhantomData<&'proc ()>
+        }
+
+        impl<'proc> #proc_ident<'proc> {
+            /// whether any smoothed or declicked field is still moving, see
+            /// `SmoothOutput::is_smoothing()`.
+            pub fn any_smoothing(&self) -> bool {
+                #( #smoothing_checks )*
+                false
+            }
+
+            /// whether any smoothed or declicked field moved since the previous block, see
+            /// `SmoothOutput::changed()`.
+            pub fn any_changed(&self) -> bool {
+                #( #changed_checks )*
+                false
+            }
         }
 
         #[doc(hidden)]
@@ -528,7 +860,8 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
 
             fn current_value<'proc>(&'proc mut self) -> Self::Process<'proc> {
                 #proc_ident {
-                    #( #current_value_fields ),*
+                    #( #current_value_fields, )*
+                    __proc: ::std::marker::PhantomData
                 }
             }
 
@@ -536,24 +869,17 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
                 #( #process_statements ;)*
 
                 #proc_ident {
-                    #( #get_process_fields ),*
+                    #( #get_process_fields, )*
+                    __proc: ::std::marker::PhantomData
                 }
             }
         }
 
         #[doc(hidden)]
-        #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
-        const #impl_params: () = {
-            use ::baseplug::parameter::{
-                Translatable,
-                TranslateFrom
-            };
-
-            impl<P: ::baseplug::Plugin> ::baseplug::Parameters<P, #smoothed_ident> for #smoothed_ident {
-                const PARAMS: &'static [&'static ::baseplug::Param<P, #smoothed_ident>] = &[
-                    #( & #parameters ),*
-                ];
-            }
-        };
+        impl<P: ::baseplug::Plugin> ::baseplug::Parameters<P, #smoothed_ident> for #smoothed_ident {
+            const PARAMS: &'static [&'static ::baseplug::Param<P, #smoothed_ident>] = &[
+                #( #parameters ),*
+            ];
+        }
     )
 }
@@ -9,8 +9,8 @@ use baseplug::{
 baseplug::model! {
     #[derive(Debug, Serialize, Deserialize)]
     struct GainModel {
-        #[model(min = -90.0, max = 3.0)]
-        #[parameter(name = "gain", unit = "Decibels",
+        #[model(min = -90.0, max = 3.0, smooth_in = "unit")]
+        #[parameter(name = "gain", unit = "Decibels", stored = "dsp",
             gradient = "Power(0.15)")]
         gain: f32
     }
@@ -19,9 +19,7 @@ baseplug::model! {
 impl Default for GainModel {
     fn default() -> Self {
         Self {
-            // "gain" is converted from dB to coefficient in the parameter handling code,
-            // so in the model here it's a coeff.
-            // -0dB == 1.0
+            // 0dB
             gain: 1.0
         }
     }
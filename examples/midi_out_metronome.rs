@@ -23,7 +23,6 @@ impl Default for MidiOutMetronomeModel {
 struct MidiOutMetronome {
     note_on: bool,
     on_ct: u64,
-    frame_ct: u64,
 }
 
 impl Plugin for MidiOutMetronome {
@@ -40,7 +39,6 @@ impl Plugin for MidiOutMetronome {
         Self {
             note_on: false,
             on_ct: 0,
-            frame_ct: 0,
         }
     }
 
@@ -50,23 +48,35 @@ impl Plugin for MidiOutMetronome {
         let output = &mut ctx.outputs[0].buffers;
         let enqueue_midi = &mut ctx.enqueue_event;
 
-        // get the current beat and tempo
-        let curr_bpm = ctx.musical_time.bpm;
-        let is_playing = ctx.musical_time.is_playing;
+        let time = ctx.musical_time;
+        let sample_rate = ctx.sample_rate as f64;
+        let is_playing = time.is_playing && time.bpm > 0.0;
+
+        // schedule from the host's timeline rather than counting frames ourselves, so that we
+        // stay on the beat when the playhead is moved.
+        let (mut next_beat, mut next_beat_frame) = time.next_beat_boundary(1.0, sample_rate);
+
+        let sixth_in_samples = time.samples_per_beat(sample_rate) / 4.0;
 
         for i in 0..ctx.nframes {
             // write silence
             output[0][i] = 0.0;
             output[1][i] = 0.0;
 
-            // calc
-            let beat_in_ms = 60_000.0 / curr_bpm;
-            let beat_in_samples = beat_in_ms * ctx.sample_rate as f64 / 1000.0;
-            let sixth_in_samples = (beat_in_samples / 4.0) * model.len[i] as f64;
-            let beat_in_samples = beat_in_samples.round() as u64;
-            let sixth_in_samples = sixth_in_samples.round() as u64;
+            let note_len = (sixth_in_samples * model.len[i] as f64).round() as u64;
 
-            if is_playing && self.frame_ct % beat_in_samples == 0 {
+            if is_playing && self.note_on && self.on_ct >= note_len {
+                // send a note off (C2)
+                let note_off = Event::<MidiOutMetronome> {
+                    frame: i,
+                    data: Data::Midi([128, 36, 0]),
+                };
+
+                enqueue_midi(note_off);
+                self.note_on = false;
+            }
+
+            if is_playing && next_beat_frame == i {
                 // send a note on (C2)
                 let note_on = Event::<MidiOutMetronome> {
                     frame: i,
@@ -76,26 +86,13 @@ impl Plugin for MidiOutMetronome {
                 enqueue_midi(note_on);
                 self.note_on = true;
                 self.on_ct = 0;
-            }
-
-            if is_playing && self.note_on && self.on_ct == sixth_in_samples {
-                // send a note off (C2)
-                let note_off = Event::<MidiOutMetronome> {
-                    frame: i,
-                    data: Data::Midi([128, 36, 0]),
-                };
 
-                enqueue_midi(note_off);
-                self.note_on = false;
+                next_beat += 1.0;
+                next_beat_frame = time.frame_of_beat(next_beat, sample_rate);
             }
 
-            if is_playing {
-                if self.note_on {
-                    self.on_ct += 1;
-                }
-                self.frame_ct += 1;
-            } else {
-                self.frame_ct = 0;
+            if self.note_on {
+                self.on_ct += 1;
             }
         }
     }
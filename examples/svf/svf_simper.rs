@@ -5,6 +5,8 @@ use std::f32::consts;
 
 use std::simd::f32x4;
 
+use baseplug::util::clamp_finite;
+
 
 pub struct SVFSimper {
     pub a1: f32x4,
@@ -17,6 +19,8 @@ pub struct SVFSimper {
 
 impl SVFSimper {
     pub fn new(cutoff: f32, resonance: f32, sample_rate: f32) -> Self {
+        // tan() blows up at nyquist
+        let cutoff = clamp_finite(cutoff, 1.0, sample_rate * 0.49);
         let g = (consts::PI * (cutoff / sample_rate)).tan();
         let k = 2f32 - (1.9f32 * resonance.min(1f32).max(0f32));
 
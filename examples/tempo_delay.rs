@@ -0,0 +1,91 @@
+use serde::{Serialize, Deserialize};
+
+use baseplug::{
+    ProcessContext,
+    Plugin,
+    DelayLine
+};
+
+
+// longest delay we allocate for. at 30bpm, two beats.
+const MAX_DELAY_SECONDS: f32 = 4.0;
+
+// used when the host doesn't tell us the tempo.
+const FALLBACK_BPM: f64 = 120.0;
+
+baseplug::model! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct TempoDelayModel {
+        #[model(min = 0.25, max = 2.0)]
+        #[parameter(name = "time", label = "beats")]
+        beats: f32,
+
+        #[model(min = 0.0, max = 0.95)]
+        #[parameter(name = "feedback")]
+        feedback: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "mix")]
+        mix: f32
+    }
+}
+
+impl Default for TempoDelayModel {
+    fn default() -> Self {
+        Self {
+            beats: 0.75,
+            feedback: 0.4,
+            mix: 0.35
+        }
+    }
+}
+
+struct TempoDelay {
+    lines: [DelayLine; 2]
+}
+
+impl Plugin for TempoDelay {
+    const NAME: &'static str = "tempo delay plug";
+    const PRODUCT: &'static str = "tempo delay plug";
+    const VENDOR: &'static str = "spicy plugins & co";
+
+    const INPUT_CHANNELS: usize = 2;
+    const OUTPUT_CHANNELS: usize = 2;
+
+    type Model = TempoDelayModel;
+
+    #[inline]
+    fn new(sample_rate: f32, _model: &TempoDelayModel) -> Self {
+        let max_delay = (MAX_DELAY_SECONDS * sample_rate) as usize;
+
+        Self {
+            lines: [DelayLine::new(max_delay), DelayLine::new(max_delay)]
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, model: &TempoDelayModelProcess, ctx: &mut ProcessContext<Self>) {
+        let input = &ctx.inputs[0].buffers;
+        let output = &mut ctx.outputs[0].buffers;
+
+        let samples_per_beat = if ctx.musical_time.bpm > 0.0 {
+            ctx.musical_time.samples_per_beat(ctx.sample_rate as f64)
+        } else {
+            (60.0 / FALLBACK_BPM) * ctx.sample_rate as f64
+        } as f32;
+
+        for i in 0..ctx.nframes {
+            let delay = model.beats[i] * samples_per_beat;
+            let mix = model.mix[i];
+
+            for (ch, line) in self.lines.iter_mut().enumerate() {
+                let dry = input[ch][i];
+                let wet = line.process(dry, delay, model.feedback[i]);
+
+                output[ch][i] = (dry * (1.0 - mix)) + (wet * mix);
+            }
+        }
+    }
+}
+
+baseplug::vst2!(TempoDelay, "tDly");
@@ -1,2 +1,34 @@
 #[macro_use]
 pub mod vst2;
+
+// a stable 16-byte class id (e.g. a VST3 IID) for a plugin, derived from its vendor and name so
+// that it doesn't have to be hand-rolled and kept in sync. the same strings always give the same
+// id, on every platform and build. it is a 128-bit FNV-1a hash of `vendor`, a nul and `name`,
+// with the version and variant bits of an RFC 4122 "custom" (version 8) UUID set.
+//
+//   const IID: [u8; 16] = baseplug::api::iid_from_name(Gain::VENDOR, Gain::NAME);
+pub const fn iid_from_name(vendor: &str, name: &str) -> [u8; 16] {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    const fn fnv1a(mut hash: u128, bytes: &[u8]) -> u128 {
+        let mut i = 0;
+
+        while i < bytes.len() {
+            hash ^= bytes[i] as u128;
+            hash = hash.wrapping_mul(PRIME);
+            i += 1;
+        }
+
+        hash
+    }
+
+    let hash = fnv1a(OFFSET, vendor.as_bytes());
+    let hash = fnv1a(hash, &[0]);
+    let hash = fnv1a(hash, name.as_bytes());
+
+    let mut iid = hash.to_be_bytes();
+    iid[6] = (iid[6] & 0x0f) | 0x80;
+    iid[8] = (iid[8] & 0x3f) | 0x80;
+    iid
+}
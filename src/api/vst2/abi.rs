@@ -1,5 +1,9 @@
+use std::panic;
+
 use super::*;
 
+// hosts vary, but past this point automation lanes and generic editors start to struggle.
+const PARAM_COUNT_WARN_THRESHOLD: usize = 1024;
 
 macro_rules! adapter_from_effect {
     ($ptr:ident) => (
@@ -7,11 +11,21 @@ macro_rules! adapter_from_effect {
     )
 }
 
+// unwinding across the C ABI into the host is undefined behaviour, so panics stop here. the panic
+// hook has already reported the panic itself by the time we get control back, so all that's left
+// is to hand the host something harmless, and to tidy up anything that the panic left half done.
 macro_rules! forward_to_adapter {
-    ($method:ident, ($($arg:ident: $ty:ty),+), $ret:ty) => {
+    ($method:ident, ($($arg:ident: $ty:ty),+), $ret:ty, $on_panic:expr) => {
         extern "C" fn $method<T: Plugin>(effect: *mut AEffect, $($arg: $ty,)+) -> $ret {
-            let adapter = unsafe { adapter_from_effect!(effect) };
-            adapter.$method($($arg,)+)
+            let ret = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                let adapter = unsafe { adapter_from_effect!(effect) };
+                adapter.$method($($arg,)+)
+            }));
+
+            match ret {
+                Ok(ret) => ret,
+                Err(_) => ($on_panic)(effect)
+            }
         }
     }
 }
@@ -19,22 +33,44 @@ macro_rules! forward_to_adapter {
 forward_to_adapter!(
     dispatch,
     (opcode: i32, index: i32, value: isize, ptr: *mut c_void, opt: f32),
-    isize);
+    isize,
+    |_| 0);
 
 forward_to_adapter!(
     get_parameter,
     (index: i32),
-    f32);
+    f32,
+    |_| 0.0);
 
 forward_to_adapter!(
     set_parameter,
     (index: i32, val: f32),
-    ());
+    (),
+    |_| ());
 
 forward_to_adapter!(
     process_replacing,
     (in_buffers: *const *const f32, out_buffers: *mut *mut f32, nframes: i32),
-    ());
+    (),
+    |effect| {
+        let adapter = unsafe { adapter_from_effect!(effect) };
+        adapter.wrapped.discard_events();
+        silence_outputs::<T>(out_buffers, nframes)
+    });
+
+fn silence_outputs<P: Plugin>(out_buffers: *mut *mut f32, nframes: i32) {
+    if out_buffers.is_null() || nframes <= 0 {
+        return;
+    }
+
+    unsafe {
+        for &buf in slice::from_raw_parts(out_buffers, P::OUTPUT_CHANNELS) {
+            if !buf.is_null() {
+                slice::from_raw_parts_mut(buf, nframes as usize).fill(0.0);
+            }
+        }
+    }
+}
 
 extern "C" fn process_deprecated(_effect: *mut AEffect, _in: *const *const f32,
     _out: *mut *mut f32, _nframes: i32)
@@ -57,6 +93,13 @@ pub fn plugin_main<P: Plugin>(host_cb: HostCallbackProc, unique_id: &[u8; 4]) ->
         flags |= effect_flags::HAS_EDITOR;
     }
 
+    let param_count = <P::Model as Model<P>>::PARAM_COUNT;
+
+    if cfg!(debug_assertions) && param_count > PARAM_COUNT_WARN_THRESHOLD {
+        eprintln!("{}: {} parameters exceeds what many VST2 hosts handle comfortably ({})",
+            P::NAME, param_count, PARAM_COUNT_WARN_THRESHOLD);
+    }
+
     let unique_id =
           (unique_id[0] as u32) << 24
         | (unique_id[1] as u32) << 16
@@ -73,7 +116,7 @@ pub fn plugin_main<P: Plugin>(host_cb: HostCallbackProc, unique_id: &[u8; 4]) ->
             get_parameter: get_parameter::<P>,
 
             num_programs: 0,
-            num_params: <P::Model as Model<P>>::Smooth::PARAMS.len() as i32,
+            num_params: param_count as i32,
             num_inputs: P::INPUT_CHANNELS as i32,
             num_outputs: P::OUTPUT_CHANNELS as i32,
 
@@ -109,7 +152,9 @@ pub fn plugin_main<P: Plugin>(host_cb: HostCallbackProc, unique_id: &[u8; 4]) ->
         wrapped: WrappedPlugin::new(),
         state: None,
 
-        output_events_buffer: OutgoingEvents::new()
+        output_events_buffer: OutgoingEvents::new(),
+
+        latency: AtomicUsize::new(0)
     });
 
     unsafe {
@@ -117,12 +162,44 @@ pub fn plugin_main<P: Plugin>(host_cb: HostCallbackProc, unique_id: &[u8; 4]) ->
     }
 }
 
+// turns the source text of a `"abcd"` or `b"abcd"` literal into a unique id, failing const
+// evaluation (and so the build) if it isn't exactly four printable ASCII characters.
+#[doc(hidden)]
+pub const fn unique_id_from_literal(lit: &str) -> [u8; 4] {
+    let lit = lit.as_bytes();
+    let start = if !lit.is_empty() && lit[0] == b'b' { 2 } else { 1 };
+
+    if lit.len() != start + 5 || lit[start - 1] != b'"' || lit[start + 4] != b'"' {
+        panic!("VST2 unique ids must be exactly 4 ASCII characters, e.g. \"tAnE\"");
+    }
+
+    let mut id = [0u8; 4];
+    let mut i = 0;
+
+    while i < 4 {
+        let c = lit[start + i];
+
+        if !c.is_ascii_graphic() || c == b'\\' {
+            panic!("VST2 unique ids must be exactly 4 printable ASCII characters, without escapes");
+        }
+
+        id[i] = c;
+        i += 1;
+    }
+
+    id
+}
+
 #[macro_export]
 macro_rules! vst2 {
-    ($plugin:ty, $unique_id:expr) => {
-        #[cfg(crate_type="bin")]
-        std::compile_error!("vst2 requires an exported main() symbol, this will conflict for example with `cargo test` and non dynamic library crates.");
+    ($plugin:ty, $unique_id:literal) => {
+        const _VST2_UNIQUE_ID: [u8; 4] =
+            $crate::api::vst2::unique_id_from_literal(stringify!($unique_id));
+
+        $crate::vst2!($plugin, &_VST2_UNIQUE_ID);
+    };
 
+    ($plugin:ty, $unique_id:expr) => {
         #[cfg(test)]
         std::compile_error!("vst2 requires an exported main() symbol, this will conflict for example with `cargo test` and non dynamic library crates.");
 
@@ -0,0 +1,88 @@
+use super::*;
+
+
+pub(super) struct VST2HostContext<'a> {
+    effect: *mut AEffect,
+    host_cb: HostCallbackProc,
+
+    // see `VST2Adapter::latency`.
+    latency: &'a AtomicUsize
+}
+
+impl<'a> VST2HostContext<'a> {
+    #[inline]
+    pub(super) fn new(effect: *mut AEffect, host_cb: HostCallbackProc,
+        latency: &'a AtomicUsize) -> Self
+    {
+        Self {
+            effect,
+            host_cb,
+            latency
+        }
+    }
+
+    #[inline]
+    fn call(&self, opcode: i32, index: i32, value: isize, ptr: *mut c_void, opt: f32) -> isize {
+        (self.host_cb)(self.effect, opcode, index, value, ptr, opt)
+    }
+}
+
+impl<'a> HostContext for VST2HostContext<'a> {
+    fn get_sample_rate(&self) -> Option<f32> {
+        match self.call(host_opcodes::GET_SAMPLE_RATE, 0, 0, ptr::null_mut(), 0.0) {
+            rate if rate > 0 => Some(rate as f32),
+            _ => None
+        }
+    }
+
+    fn get_block_size(&self) -> Option<usize> {
+        match self.call(host_opcodes::GET_BLOCK_SIZE, 0, 0, ptr::null_mut(), 0.0) {
+            size if size > 0 => Some(size as usize),
+            _ => None
+        }
+    }
+
+    fn get_tempo(&self) -> Option<f64> {
+        let vti = self.call(host_opcodes::GET_TIME, 0,
+            time_info_flags::TEMPO_VALID as isize, ptr::null_mut(), 0.0);
+
+        if vti == 0 {
+            return None;
+        }
+
+        let time_info = unsafe { &*(vti as *const TimeInfo) };
+
+        if (time_info.flags & time_info_flags::TEMPO_VALID) != 0 {
+            Some(time_info.tempo)
+        } else {
+            None
+        }
+    }
+
+    fn automate(&self, index: usize, value: f32) {
+        self.call(host_opcodes::AUTOMATE, index as i32, 0, ptr::null_mut(), value);
+    }
+
+    // only recorded here, since this is usually called from `process()`. the host hears about it
+    // from `VST2Adapter::flush_latency()`.
+    fn report_latency_changed(&self, samples: usize) {
+        self.latency.store(samples, Ordering::Relaxed);
+    }
+
+    fn get_vendor_string(&self) -> Option<String> {
+        let mut buf = [0u8; MAX_VENDOR_STR_LEN];
+
+        let ret = self.call(host_opcodes::GET_VENDOR_STRING, 0, 0,
+            buf.as_mut_ptr() as *mut c_void, 0.0);
+
+        if ret == 0 {
+            return None;
+        }
+
+        // guard against hosts that fill the whole buffer without terminating it.
+        buf[MAX_VENDOR_STR_LEN - 1] = 0;
+
+        let vendor = unsafe { CStr::from_ptr(buf.as_ptr() as *const c_char) };
+        Some(vendor.to_string_lossy().into_owned())
+    }
+}
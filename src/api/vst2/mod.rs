@@ -3,6 +3,7 @@ use std::os::raw::c_void;
 use std::ptr;
 use std::{io, os::raw::c_char};
 use std::{mem, slice};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 pub use vst2_sys;
 use vst2_sys::*;
@@ -14,7 +15,10 @@ mod ui;
 use ui::*;
 
 mod abi;
-pub use abi::plugin_main;
+pub use abi::{plugin_main, unique_id_from_literal};
+
+mod host;
+use host::*;
 
 const MAX_PARAM_STR_LEN: usize = 32;
 const MAX_EFFECT_NAME_LEN: usize = 32;
@@ -23,6 +27,22 @@ const MAX_PRODUCT_STR_LEN: usize = 64;
 
 const TRANSPORT_PLAYING: i32 = 2;
 
+// not exported by vst2-sys
+const SET_PROCESS_PRECISION: i32 = 77;
+const PROCESS_PRECISION_32: isize = 0;
+const GET_MIDI_KEY_NAME: i32 = 66;
+
+const MAX_KEY_NAME_LEN: usize = 64;
+
+#[repr(C)]
+struct MidiKeyName {
+    this_program_index: i32,
+    this_key_number: i32,
+    key_name: [c_char; MAX_KEY_NAME_LEN],
+    reserved: i32,
+    flags: i32
+}
+
 // output events buffer size
 const OUTPUT_BUFFER_SIZE: usize = 256;
 
@@ -33,7 +53,13 @@ fn cstr_as_slice<'a>(ptr: *mut c_void, len: usize) -> &'a mut [u8] {
     }
 }
 
+// copies `src` into the `max_len`-byte buffer at `ptr`, truncating it to leave room for the nul.
 fn cstrcpy(ptr: *mut c_void, src: &str, max_len: usize) {
+    // no room for even the nul.
+    if max_len == 0 {
+        return;
+    }
+
     let dest = cstr_as_slice(ptr, max_len);
     let src_bytes = src.as_bytes();
     let len = src_bytes.len().min(max_len - 1);
@@ -42,6 +68,22 @@ fn cstrcpy(ptr: *mut c_void, src: &str, max_len: usize) {
     dest[len] = 0;
 }
 
+// channel `i` of a host buffer array holding `count` channels, `None` if it isn't there.
+#[inline]
+unsafe fn host_channel(buffers: *const *const f32, count: usize, i: usize) -> Option<*const f32> {
+    if buffers.is_null() || i >= count {
+        return None;
+    }
+
+    let buf = *buffers.add(i);
+
+    if buf.is_null() {
+        None
+    } else {
+        Some(buf)
+    }
+}
+
 #[inline]
 fn param_for_vst2_id<P, M>(id: i32) -> Option<&'static Param<P, M::Smooth>>
     where
@@ -110,29 +152,59 @@ struct VST2Adapter<P: Plugin> {
 
     // output events buffer
     output_events_buffer: OutgoingEvents,
+
+    // the latency the plugin last reported, written from whichever thread it reported it on.
+    // see `flush_latency()`.
+    latency: AtomicUsize
 }
 
 impl<P: Plugin> VST2Adapter<P> {
     #[inline]
     fn dispatch(&mut self, opcode: i32, index: i32, value: isize, ptr: *mut c_void, opt: f32) -> isize {
+        // PROCESS_EVENTS comes from the audio thread, and CLOSE is no time to bother the host.
+        if opcode != effect_opcodes::PROCESS_EVENTS && opcode != effect_opcodes::CLOSE {
+            self.flush_latency();
+        }
+
         match opcode {
             ////
             // lifecycle
             ////
             effect_opcodes::CLOSE => {
+                // some hosts close the plugin without closing the editor first. tear the UI down
+                // while the plugin it talks to is still alive.
+                self.ui_close();
+
                 unsafe {
                     drop(Box::from_raw(self))
                 };
             },
 
-            effect_opcodes::SET_SAMPLE_RATE => self.wrapped.set_sample_rate(opt),
+            // VST2 has no way to refuse a sample rate, hosts ignore what we return here.
+            effect_opcodes::SET_SAMPLE_RATE => {
+                self.wrapped.set_sample_rate(opt);
+            },
+
+            effect_opcodes::SET_BLOCK_SIZE if value > 0 =>
+                self.wrapped.set_max_block_size(value as usize),
 
             effect_opcodes::MAINS_CHANGED => {
                 if value == 1 {
                     self.wrapped.reset();
+                } else {
+                    self.wrapped.all_notes_off();
                 }
             },
 
+            effect_opcodes::START_PROCESS => self.wrapped.start_processing(),
+            effect_opcodes::STOP_PROCESS => self.wrapped.stop_processing(),
+
+            // we only have a 32-bit process path, so refuse anything else and let the host keep
+            // calling processReplacing.
+            SET_PROCESS_PRECISION => {
+                return (value == PROCESS_PRECISION_32) as isize;
+            },
+
             ////
             // parameters
             ////
@@ -150,11 +222,12 @@ impl<P: Plugin> VST2Adapter<P> {
 
             effect_opcodes::GET_PARAM_DISPLAY => {
                 let param = param_for_idx!(index);
+
                 let dest = cstr_as_slice(ptr, MAX_PARAM_STR_LEN);
                 let mut cursor = io::Cursor::new(
                     &mut dest[..MAX_PARAM_STR_LEN - 1]);
 
-                match param.get_display(&self.wrapped.smoothed_model, &mut cursor) {
+                match self.wrapped.get_parameter_display(param, &mut cursor) {
                     Ok(_) => {
                         let len = cursor.position();
                         dest[len as usize] = 0;
@@ -168,7 +241,43 @@ impl<P: Plugin> VST2Adapter<P> {
                 }
             },
 
-            effect_opcodes::CAN_BE_AUTOMATED => return 1,
+            effect_opcodes::CAN_BE_AUTOMATED => {
+                let param = param_for_idx!(index);
+                return param.automatable as isize;
+            },
+
+            effect_opcodes::GET_PARAMETER_PROPERTIES => {
+                let param = param_for_idx!(index);
+
+                if ptr.is_null() {
+                    return 0;
+                }
+
+                let props = unsafe { &mut *(ptr as *mut ParameterProperties) };
+
+                // hosts don't all clear this before asking, and read fields the flags don't
+                // mention.
+                *props = unsafe { mem::zeroed() };
+
+                // numeric parameters are continuous, so they have no steps to report.
+                if let parameter::Type::Switch { .. } = param.param_type {
+                    props.flags = parameter_flags::IS_SWITCH
+                        | parameter_flags::USES_INTEGER_MIN_MAX
+                        | parameter_flags::USES_INT_STEP;
+
+                    props.min_integer = 0;
+                    props.max_integer = 1;
+                    props.step_integer = 1;
+                    props.large_step_integer = 1;
+                }
+
+                cstrcpy(props.label.as_mut_ptr() as *mut c_void,
+                    param.name, string_constants::MAX_LABEL_LEN);
+                cstrcpy(props.short_label.as_mut_ptr() as *mut c_void,
+                    param.get_name(), string_constants::MAX_SHORT_LABEL_LEN);
+
+                return 1;
+            },
 
             ////
             // plugin metadata
@@ -201,8 +310,10 @@ impl<P: Plugin> VST2Adapter<P> {
                 for ev in ev_slice {
                     if (**ev).event_type == MIDI_TYPE {
                         let ev = *ev as *const MidiEvent;
+
+                        // negative offsets are a host bug, play them at the top of the block.
                         self.wrapped.midi_input(
-                            (*ev).delta_frames as usize,
+                            (*ev).delta_frames.max(0) as usize,
                             [(*ev).midi_data[0], (*ev).midi_data[1], (*ev).midi_data[2]]
                         );
                     }
@@ -211,13 +322,40 @@ impl<P: Plugin> VST2Adapter<P> {
                 return 0;
             },
 
+            GET_MIDI_KEY_NAME => {
+                if ptr.is_null() {
+                    return 0;
+                }
+
+                let key_name = unsafe { &mut *(ptr as *mut MidiKeyName) };
+
+                let name = match key_name.this_key_number {
+                    note @ 0..=127 => self.wrapped.midi_key_name(note as u8),
+                    _ => None
+                };
+
+                return match name {
+                    Some(name) => {
+                        cstrcpy(key_name.key_name.as_mut_ptr() as *mut c_void,
+                            name, MAX_KEY_NAME_LEN);
+                        1
+                    },
+
+                    None => 0
+                };
+            },
+
             ////
             // state
             ////
             effect_opcodes::GET_CHUNK => {
-                let new_state = match self.wrapped.serialise() {
-                    None => return 0,
-                    Some(s) => s
+                // VST2 has no way to report a failed save, so store the defaults rather than
+                // nothing. if even that fails, a zero length tells the host there's no chunk.
+                let new_state = match self.wrapped.serialise()
+                    .or_else(|_| WrappedPlugin::<P>::serialise_default())
+                {
+                    Ok(s) => s,
+                    Err(_) => return 0
                 };
 
                 unsafe {
@@ -274,7 +412,10 @@ impl<P: Plugin> VST2Adapter<P> {
                 };
             },
 
-            effect_opcodes::EDIT_IDLE => {},
+            effect_opcodes::EDIT_IDLE => {
+                let host = VST2HostContext::new(&mut self.effect, self.host_cb, &self.latency);
+                self.wrapped.poll_ui_messages(&host);
+            },
 
             effect_opcodes::EDIT_CLOSE => {
                 self.ui_close();
@@ -350,15 +491,15 @@ impl<P: Plugin> VST2Adapter<P> {
             }
         };
 
-        if (time_info.flags | time_info_flags::TEMPO_VALID) != 0 {
+        if (time_info.flags & time_info_flags::TEMPO_VALID) != 0 {
             mtime.bpm = time_info.tempo;
         }
 
-        if (time_info.flags | time_info_flags::PPQ_POS_VALID) != 0 {
+        if (time_info.flags & time_info_flags::PPQ_POS_VALID) != 0 {
             mtime.beat = time_info.ppq_pos;
         }
 
-        if (time_info.flags | TRANSPORT_PLAYING) != 0 {
+        if (time_info.flags & TRANSPORT_PLAYING) != 0 {
             mtime.is_playing = true;
         }
 
@@ -371,63 +512,85 @@ impl<P: Plugin> VST2Adapter<P> {
         out_buffers: *mut *mut f32,
         nframes: i32)
     {
-        let input = unsafe {
-            let b = slice::from_raw_parts(in_buffers, 2);
-
-            [slice::from_raw_parts(b[0], nframes as usize),
-             slice::from_raw_parts(b[1], nframes as usize)]
+        // the host hands us as many channels as we declared, at most. go by that rather than by
+        // what we use, and treat null channels as missing.
+        let (input_ptrs, output_ptrs) = unsafe {
+            let out_buffers = out_buffers as *const *const f32;
+
+            ([host_channel(in_buffers, P::INPUT_CHANNELS, 0),
+              host_channel(in_buffers, P::INPUT_CHANNELS, 1)],
+             [host_channel(out_buffers, P::OUTPUT_CHANNELS, 0),
+              host_channel(out_buffers, P::OUTPUT_CHANNELS, 1)])
         };
 
-        let output = unsafe {
-            let b = slice::from_raw_parts(out_buffers, 2);
+        // an input sharing its buffer with an output (in-place processing) mustn't be borrowed
+        // alongside it, the wrapper reads it out of the output instead.
+        let input = input_ptrs.map(|buf| buf.map(|buf| {
+            match output_ptrs.iter().position(|&out| out == Some(buf)) {
+                Some(o) => InputChannel::Output(o),
+                None => InputChannel::Buffer(unsafe {
+                    slice::from_raw_parts(buf, nframes as usize)
+                })
+            }
+        }));
 
-            [slice::from_raw_parts_mut(b[0], nframes as usize),
-             slice::from_raw_parts_mut(b[1], nframes as usize)]
-        };
+        let output = output_ptrs.map(|buf| buf.map(|buf| unsafe {
+            slice::from_raw_parts_mut(buf as *mut f32, nframes as usize)
+        }));
 
         let musical_time = self.get_musical_time();
-        self.wrapped.process(musical_time, input, output, nframes as usize);
+        let host = VST2HostContext::new(&mut self.effect, self.host_cb, &self.latency);
+        self.wrapped.process(musical_time, input, output, nframes as usize, &host);
 
         // write output_events in the buffer
         self.send_output_events();
+    }
+
+    // VST2 has no dedicated latency notification, hosts re-read `initial_delay` on IO_CHANGED.
+    // hosts may re-initialise the plugin in response, so that can't be sent from `process()`, and
+    // is sent from the next dispatcher call that isn't made on the audio thread instead.
+    fn flush_latency(&mut self) {
+        let latency = self.latency.load(Ordering::Relaxed) as i32;
+
+        if self.effect.initial_delay == latency {
+            return;
+        }
 
-        // clear
-        self.wrapped.output_events.clear();
+        self.effect.initial_delay = latency;
+        (self.host_cb)(&mut self.effect, host_opcodes::IO_CHANGED, 0, 0, ptr::null_mut(), 0.0);
     }
 
     #[inline]
     fn send_output_events(&mut self) {
         self.output_events_buffer.num_events = 0;
 
+        // only MIDI goes out to the host, filter before zipping so that skipped events don't leave
+        // holes in the output buffer.
+        let midi_events = self.wrapped.drain_output_events()
+            .filter_map(|ev| match ev.data {
+                event::Data::Midi(midi_data) => Some((ev.frame, midi_data)),
+                _ => None
+            });
+
         // write into output buffer
-        for (bevt, ev) in self
-            .wrapped
-            .output_events
-            .iter()
+        for ((frame, midi_data), ev) in midi_events
             .zip(self.output_events_buffer.events.iter_mut())
         {
-            match bevt.data {
-                event::Data::Midi(midi_data) => {
-                    let midi_event: MidiEvent = MidiEvent {
-                        event_type: MIDI_TYPE,
-                        byte_size: mem::size_of::<MidiEvent>() as i32,
-                        delta_frames: bevt.frame as i32,
-                        flags: 1,
-                        note_length: 0,
-                        note_offset: 0,
-                        midi_data: [midi_data[0], midi_data[1], midi_data[2], 0],
-                        detune: 0,
-                        note_off_velocity: 0,
-                        reserved_1: 0,
-                        reserved_2: 0,
-                    };
-                    *ev = midi_event;
-
-                    self.output_events_buffer.num_events += 1;
-                }
-
-                _ => {}
-            }
+            *ev = MidiEvent {
+                event_type: MIDI_TYPE,
+                byte_size: mem::size_of::<MidiEvent>() as i32,
+                delta_frames: frame as i32,
+                flags: 1,
+                note_length: 0,
+                note_offset: 0,
+                midi_data: [midi_data[0], midi_data[1], midi_data[2], 0],
+                detune: 0,
+                note_off_velocity: 0,
+                reserved_1: 0,
+                reserved_2: 0,
+            };
+
+            self.output_events_buffer.num_events += 1;
         }
 
         if self.output_events_buffer.num_events > 0 {
@@ -1,51 +1,91 @@
-use raw_window_handle::{RawWindowHandle, HasRawWindowHandle};
+use super::*;
 
+// raw-window-handle only knows how to describe a parent window on these platforms. elsewhere, a
+// plugin with a UI still builds but runs headless: `has_ui()` reports false to the host, so the
+// editor is never opened.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "windows",
+    target_os = "macos"
+))]
+mod handle {
+    use raw_window_handle::{RawWindowHandle, HasRawWindowHandle};
+
+    use super::*;
+
+    pub(super) const UI_SUPPORTED: bool = true;
+
+    pub(super) struct VST2WindowHandle(pub(super) *mut c_void);
+
+    impl From<&VST2WindowHandle> for RawWindowHandle {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        fn from(handle: &VST2WindowHandle) -> RawWindowHandle {
+            use raw_window_handle::unix::*;
+
+            RawWindowHandle::Xcb(XcbHandle {
+                window: handle.0 as u32,
+                ..XcbHandle::empty()
+            })
+        }
 
-use super::*;
+        #[cfg(target_os = "windows")]
+        fn from(handle: &VST2WindowHandle) -> RawWindowHandle {
+            use raw_window_handle::windows::*;
 
-struct VST2WindowHandle(*mut c_void);
-
-impl From<&VST2WindowHandle> for RawWindowHandle {
-    #[cfg(any(
-        target_os = "linux",
-        target_os = "dragonfly",
-        target_os = "freebsd",
-        target_os = "netbsd",
-        target_os = "openbsd"
-    ))]
-    fn from(handle: &VST2WindowHandle) -> RawWindowHandle {
-        use raw_window_handle::unix::*;
-
-        RawWindowHandle::Xcb(XcbHandle {
-            window: handle.0 as u32,
-            ..XcbHandle::empty()
-        })
-    }
+            RawWindowHandle::Windows(WindowsHandle {
+                hwnd: handle.0,
+                ..WindowsHandle::empty()
+            })
+        }
 
-    #[cfg(target_os = "windows")]
-    fn from(handle: &VST2WindowHandle) -> RawWindowHandle {
-        use raw_window_handle::windows::*;
+        #[cfg(target_os = "macos")]
+        fn from(handle: &VST2WindowHandle) -> RawWindowHandle {
+            use raw_window_handle::macos::*;
 
-        RawWindowHandle::Windows(WindowsHandle {
-            hwnd: handle.0,
-            ..WindowsHandle::empty()
-        })
+            RawWindowHandle::MacOS(MacOSHandle {
+                ns_view: handle.0,
+                ..MacOSHandle::empty()
+            })
+        }
     }
 
-    #[cfg(target_os = "macos")]
-    fn from(handle: &VST2WindowHandle) -> RawWindowHandle {
-        use raw_window_handle::macos::*;
+    unsafe impl HasRawWindowHandle for VST2WindowHandle {
+        fn raw_window_handle(&self) -> RawWindowHandle {
+            self.into()
+        }
+    }
 
-        RawWindowHandle::MacOS(MacOSHandle {
-            ns_view: handle.0,
-            ..MacOSHandle::empty()
-        })
+    pub(super) fn open<P: PluginUI>(parent: *mut c_void) -> WindowOpenResult<P::Handle> {
+        P::ui_open(&VST2WindowHandle(parent))
     }
 }
 
-unsafe impl HasRawWindowHandle for VST2WindowHandle {
-    fn raw_window_handle(&self) -> RawWindowHandle {
-        self.into()
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "windows",
+    target_os = "macos"
+)))]
+mod handle {
+    use super::*;
+
+    pub(super) const UI_SUPPORTED: bool = false;
+
+    pub(super) fn open<P: PluginUI>(_parent: *mut c_void) -> WindowOpenResult<P::Handle> {
+        Err(())
     }
 }
 
@@ -75,19 +115,24 @@ impl<P: Plugin> VST2UI for VST2Adapter<P> {
 
 impl<P: PluginUI> VST2UI for VST2Adapter<P> {
     fn has_ui() -> bool {
-        true
+        handle::UI_SUPPORTED
     }
 
     fn ui_get_rect(&self) -> Option<(i16, i16)> {
-        Some(P::ui_size())
+        if handle::UI_SUPPORTED {
+            Some(P::ui_size())
+        } else {
+            None
+        }
     }
 
     fn ui_open(&mut self, parent: *mut c_void) -> WindowOpenResult<()> {
-        let parent = VST2WindowHandle(parent);
-
         if self.wrapped.ui_handle.is_none() {
-            P::ui_open(&parent)
-                .map(|handle| self.wrapped.ui_handle = Some(handle))
+            handle::open::<P>(parent)
+                .map(|handle| {
+                    self.wrapped.ui_handle = Some(handle);
+                    self.wrapped.ui_host_info();
+                })
         } else {
             Ok(())
         }
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::collections::VecDeque;
 
 use crate::{
     Smooth,
@@ -6,7 +7,10 @@ use crate::{
 };
 
 const DECLICK_SETTLE: f32 = 0.001;
+const DEFAULT_QUEUE_DEPTH: usize = 4;
 
+// `to` is the value being faded to in this block. values set while this fade is running are
+// queued behind it, so `to` isn't necessarily where the fades end up, see `Declick::dest()`.
 pub struct DeclickOutput<'a, T> {
     pub from: &'a T,
     pub to: &'a T,
@@ -15,10 +19,41 @@ pub struct DeclickOutput<'a, T> {
     pub status: SmoothStatus
 }
 
+impl<'a, T> DeclickOutput<'a, T> {
+    // the value being faded to, or the settled value if there's no fade. for when the plugin only
+    // wants to know the latest choice and doesn't crossfade.
+    #[inline]
+    pub fn current(&self) -> &'a T {
+        self.to
+    }
+
+    // the settled value, `None` while a fade is in progress.
+    #[inline]
+    pub fn value(&self) -> Option<&'a T> {
+        match self.status {
+            SmoothStatus::Inactive => Some(self.to),
+            _ => None
+        }
+    }
+
+    // mixes frame `i` of the output rendered with `from` and of the output rendered with `to`.
+    #[inline]
+    pub fn crossfade(&self, i: usize, from: f32, to: f32) -> f32 {
+        match self.status {
+            SmoothStatus::Inactive => to,
+            _ => from + ((to - from) * self.fade[i])
+        }
+    }
+}
+
 pub struct Declick<T: Sized + Clone> {
     current: T,
     next: Option<T>,
-    staged: Option<T>,
+
+    // values set while a fade was already running, each faded to in turn once the one before it
+    // has finished. never grows past `queue_depth`, so this doesn't allocate after construction.
+    staged: VecDeque<T>,
+    queue_depth: usize,
 
     fade: Smooth<f32>
 }
@@ -27,10 +62,20 @@ impl<T> Declick<T>
     where T: Sized + Clone + Eq
 {
     pub fn new(initial: T) -> Self {
+        Self::with_queue_depth(initial, DEFAULT_QUEUE_DEPTH)
+    }
+
+    // `depth` is how many values can be waiting behind the one currently being faded to. once
+    // the queue is full, further sets replace the most recently queued value.
+    pub fn with_queue_depth(initial: T, depth: usize) -> Self {
+        let depth = depth.max(1);
+
         Self {
             current: initial,
             next: None,
-            staged: None,
+
+            staged: VecDeque::with_capacity(depth),
+            queue_depth: depth,
 
             fade: Smooth::new(0.0)
         }
@@ -39,7 +84,7 @@ impl<T> Declick<T>
     pub fn reset(&mut self, to: T) {
         self.current = to;
         self.next = None;
-        self.staged = None;
+        self.staged.clear();
 
         self.fade.reset(0.0);
     }
@@ -51,14 +96,26 @@ impl<T> Declick<T>
 
         if self.next.is_none() {
             self.next = Some(to);
-
-            self.fade.reset(0.0);
-            self.fade.set(1.0);
+            self.restart_fade();
+        } else if self.staged.len() < self.queue_depth {
+            self.staged.push_back(to);
         } else {
-            self.staged = Some(to);
+            // full, so the most recently queued value is replaced. if that would leave the queue
+            // fading to the value it's already at, drop it instead.
+            self.staged.pop_back();
+
+            if self.staged.back().or(self.next.as_ref()) != Some(&to) {
+                self.staged.push_back(to);
+            }
         }
     }
 
+    #[inline]
+    fn restart_fade(&mut self) {
+        self.fade.reset(0.0);
+        self.fade.set(1.0);
+    }
+
     pub fn set_speed_ms(&mut self, sample_rate: f32, ms: f32) {
         self.fade.set_speed_ms(sample_rate, ms);
     }
@@ -89,9 +146,11 @@ impl<T> Declick<T>
         }
     }
 
+    // where the fades end up once every queued value has had its turn. this is what `set()`
+    // compares against, so setting the destination again is a no-op.
     #[inline]
     pub fn dest(&self) -> &T {
-        self.staged.as_ref()
+        self.staged.back()
             .or_else(|| self.next.as_ref())
             .unwrap_or(&self.current)
     }
@@ -101,6 +160,11 @@ impl<T> Declick<T>
         self.next.is_some()
     }
 
+    #[inline]
+    pub fn status(&self) -> SmoothStatus {
+        self.fade.status()
+    }
+
     #[inline]
     pub fn process(&mut self, nframes: usize) {
         self.fade.process(nframes);
@@ -118,7 +182,11 @@ impl<T> Declick<T>
         }
 
         self.current = self.next.take().unwrap();
-        self.next = self.staged.take();
+        self.next = self.staged.pop_front();
+
+        if self.next.is_some() {
+            self.restart_fade();
+        }
     }
 }
 
@@ -0,0 +1,72 @@
+use std::fmt;
+
+// a fixed-length delay line with linearly-interpolated fractional reads.
+//
+// all memory is allocated up-front in `new()`, so reads and writes are realtime-safe.
+pub struct DelayLine {
+    buffer: Vec<f32>,
+    write_pos: usize
+}
+
+impl DelayLine {
+    // panics if `max_delay` is 0, since the shortest delay that can be read is a whole sample.
+    pub fn new(max_delay: usize) -> Self {
+        assert!(max_delay > 0, "a delay line needs a max_delay of at least one sample");
+
+        Self {
+            // one extra sample for the interpolation partner at `max_delay`, and one because the
+            // shortest possible delay is a single sample.
+            buffer: vec![0.0; max_delay + 2],
+            write_pos: 0
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.write_pos = 0;
+    }
+
+    #[inline]
+    pub fn max_delay(&self) -> usize {
+        self.buffer.len() - 2
+    }
+
+    // read the signal from `delay` samples ago. `delay` is clamped to `1.0..=max_delay()`, since a
+    // delay shorter than one sample would need the sample that hasn't been written yet.
+    #[inline]
+    pub fn read(&self, delay: f32) -> f32 {
+        let delay = delay.max(1.0).min(self.max_delay() as f32);
+
+        let whole = delay as usize;
+        let frac = delay - (whole as f32);
+
+        let len = self.buffer.len();
+        let a = self.buffer[(self.write_pos + len - whole) % len];
+        let b = self.buffer[(self.write_pos + len - whole - 1) % len];
+
+        a + ((b - a) * frac)
+    }
+
+    #[inline]
+    pub fn write(&mut self, x: f32) {
+        self.buffer[self.write_pos] = x;
+        self.write_pos = (self.write_pos + 1) % self.buffer.len();
+    }
+
+    // read, then write `input` plus `feedback` times what was read. returns the delayed signal.
+    #[inline]
+    pub fn process(&mut self, input: f32, delay: f32, feedback: f32) -> f32 {
+        let out = self.read(delay);
+        self.write(input + (out * feedback));
+        out
+    }
+}
+
+impl fmt::Debug for DelayLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayLine")
+            .field("max_delay", &self.max_delay())
+            .field("write_pos", &self.write_pos)
+            .finish()
+    }
+}
@@ -9,6 +9,12 @@ use crate::{
 pub enum Data<P: Plugin> {
     Midi([u8; 3]),
 
+    // MIDI system real-time clock tick (0xF8), 24 per quarter note.
+    Clock,
+
+    // MIDI song position pointer (0xF2), in sixteenth notes since the start of the song.
+    SongPosition(u16),
+
     Parameter {
         param: &'static Param<P, <P::Model as Model<P>>::Smooth>,
         val: f32
@@ -32,6 +38,14 @@ impl<P: Plugin> fmt::Debug for Data<P> {
                     .field(&m)
                     .finish(),
 
+            Data::Clock =>
+                f.write_str("Data::Clock"),
+
+            Data::SongPosition(pos) =>
+                f.debug_tuple("Data::SongPosition")
+                    .field(&pos)
+                    .finish(),
+
             Data::Parameter { param, val } =>
                 f.debug_struct("Data::Parameter")
                     .field("param", &param)
@@ -0,0 +1,31 @@
+// queries a plugin can make of its host, outside of the regular parameter and event flow.
+//
+// these go straight through to the host, and most hosts make no promises about them being
+// realtime-safe. calling them from `process()` may lock or allocate on the host's side, so prefer
+// caching the results or calling them sparingly. methods return `None` if the host doesn't know
+// or doesn't answer.
+pub trait HostContext {
+    fn get_sample_rate(&self) -> Option<f32>;
+    fn get_block_size(&self) -> Option<usize>;
+
+    // the host's current tempo in BPM, regardless of whether audio is running. meant for UIs that
+    // want to show tempo while the transport is stopped – inside `process()`, use
+    // `ProcessContext::musical_time` instead.
+    fn get_tempo(&self) -> Option<f64>;
+
+    // tells the host that the parameter at `index` (as in `Parameters::PARAMS`) changed to the
+    // normalised `value` on the plugin's own initiative, so that it can update its automation
+    // and any generic editor.
+    fn automate(&self, index: usize, value: f32);
+
+    // tells the host that the plugin's latency is now `samples`, e.g. after switching
+    // oversampling on or off. does nothing if the latency hasn't changed. unlike the rest of this
+    // trait, this is realtime-safe and meant to be called from `process()`: the new latency is
+    // only recorded, and the host is told later from its main thread (for VST2, on its next
+    // dispatcher call). hosts may re-initialise the plugin in response, so avoid calling this on
+    // every block with a different value.
+    fn report_latency_changed(&self, samples: usize);
+
+    // allocates, never call this from the audio thread.
+    fn get_vendor_string(&self) -> Option<String>;
+}
@@ -0,0 +1,220 @@
+use crate::{
+    Plugin,
+    Model,
+    Parameters,
+    MusicalTime,
+    HostContext,
+    Event
+};
+
+use crate::event::Data;
+
+use crate::wrapper::*;
+
+// a plugin instance driven directly from Rust, without a plugin host in between. useful for
+// embedding plugins in applications and for exercising them in tests.
+pub struct Instance<P: Plugin> {
+    wrapped: WrappedPlugin<P>,
+    musical_time: MusicalTime
+}
+
+impl<P: Plugin> Instance<P> {
+    pub fn new(sample_rate: f32) -> Self {
+        Self::with_capacity(sample_rate,
+            DEFAULT_EVENT_CAPACITY, DEFAULT_OUTPUT_EVENT_CAPACITY)
+    }
+
+    // `event_capacity` bounds how many events can be queued for a single `process()` call, and
+    // `output_event_capacity` how many the plugin can emit during one, before the queues have to
+    // grow (and allocate).
+    pub fn with_capacity(sample_rate: f32, event_capacity: usize, output_event_capacity: usize)
+        -> Self
+    {
+        let mut wrapped = WrappedPlugin::with_capacity(event_capacity, output_event_capacity);
+        wrapped.set_sample_rate(sample_rate);
+
+        Self {
+            wrapped,
+            musical_time: MusicalTime {
+                bpm: 120.0,
+                beat: 0.0,
+                is_playing: false
+            }
+        }
+    }
+
+    #[inline]
+    pub fn sample_rate(&self) -> f32 {
+        self.wrapped.sample_rate()
+    }
+
+    // rebuilds the plugin at `sample_rate`. returns false, and leaves the plugin silent, if it
+    // doesn't support that rate. see `Plugin::supports_sample_rate()`.
+    pub fn set_sample_rate(&mut self, sample_rate: f32) -> bool {
+        self.wrapped.set_sample_rate(sample_rate)
+    }
+
+    // the transport as seen by the plugin from the next `process()` on. while playing, `beat`
+    // advances by the length of each processed block.
+    pub fn set_transport(&mut self, bpm: f64, is_playing: bool, beat: f64) {
+        self.musical_time = MusicalTime {
+            bpm,
+            beat,
+            is_playing
+        };
+    }
+
+    #[inline]
+    pub fn musical_time(&self) -> &MusicalTime {
+        &self.musical_time
+    }
+
+    // queues a MIDI message for the next `process()`, `frame` frames into the block. ignored if
+    // the plugin isn't a `MidiReceiver`.
+    #[inline]
+    pub fn midi_input(&mut self, frame: usize, data: [u8; 3]) {
+        self.wrapped.midi_input(frame, data);
+    }
+
+    // the normalised value of the parameter at `index` (as in `Parameters::PARAMS`), `None` if
+    // there's no such parameter.
+    pub fn get_parameter(&self, index: usize) -> Option<f32> {
+        <P::Model as Model<P>>::Smooth::PARAMS.get(index)
+            .map(|param| self.wrapped.get_parameter(param))
+    }
+
+    // sets the parameter at `index` the way a host would, taking effect from the start of the
+    // next `process()`.
+    pub fn set_parameter(&mut self, index: usize, normalised: f32) {
+        if let Some(param) = <P::Model as Model<P>>::Smooth::PARAMS.get(index) {
+            self.wrapped.set_parameter(param, normalised);
+        }
+    }
+
+    // a JSON snapshot of the model, the sample rate, and each parameter's normalised value and
+    // smoothing status, for debugging and tooling. allocates.
+    #[inline]
+    pub fn dump_state(&self) -> serde_json::Value {
+        self.wrapped.dump_state()
+    }
+
+    pub fn reset_parameter(&mut self, index: usize) {
+        let host = InstanceHost {
+            sample_rate: self.wrapped.sample_rate(),
+            musical_time: &self.musical_time
+        };
+
+        self.wrapped.reset_parameter(index, &host);
+    }
+
+    // queues sample-accurate changes to the parameter at `index` (as in `Parameters::PARAMS`) for
+    // the next `process()`, as `(frame, normalised value)` pairs. the block is split at each
+    // frame, so the plugin sees the new value from exactly that frame on. ignored if there's no
+    // parameter at `index`.
+    pub fn automate(&mut self, index: usize, points: &[(usize, f32)]) {
+        let param = match <P::Model as Model<P>>::Smooth::PARAMS.get(index) {
+            Some(param) => *param,
+            None => return
+        };
+
+        for &(frame, val) in points {
+            self.wrapped.enqueue_event(Event {
+                frame,
+                data: Data::Parameter {
+                    param,
+                    val
+                }
+            });
+        }
+    }
+
+    pub fn process(&mut self, input: [&[f32]; 2], output: [&mut [f32]; 2], nframes: usize) {
+        let host = InstanceHost {
+            sample_rate: self.wrapped.sample_rate(),
+            musical_time: &self.musical_time
+        };
+
+        let [left, right] = output;
+        let input = input.map(|buf| Some(InputChannel::Buffer(buf)));
+
+        self.wrapped.process(self.musical_time.clone(), input, [Some(left), Some(right)], nframes,
+            &host);
+
+        if self.musical_time.is_playing {
+            let sample_rate = self.wrapped.sample_rate();
+            self.musical_time.step_by_samples(sample_rate.into(), nframes);
+        }
+    }
+
+    // processes silence until no parameter is smoothing or declicking any more, for at most
+    // `max_frames` frames, and returns whether everything settled in time. useful before
+    // rendering a tail or measuring a steady state. the output is discarded.
+    pub fn settle(&mut self, max_frames: usize) -> bool {
+        let host = InstanceHost {
+            sample_rate: self.wrapped.sample_rate(),
+            musical_time: &self.musical_time
+        };
+
+        let processed = self.wrapped.settle(self.musical_time.clone(), max_frames, &host);
+
+        if self.musical_time.is_playing {
+            let sample_rate = self.wrapped.sample_rate();
+            self.musical_time.step_by_samples(sample_rate.into(), processed);
+        }
+
+        self.wrapped.is_settled()
+    }
+
+    // allocating convenience wrapper around `process()`, not for use on an audio thread. missing
+    // input channels are treated as silence, and every input channel has to hold at least
+    // `nframes` samples. returns `P::OUTPUT_CHANNELS` channels of `nframes` samples each.
+    pub fn process_owned(&mut self, inputs: &[Vec<f32>], nframes: usize) -> Vec<Vec<f32>> {
+        let silence = vec![0.0; nframes];
+        let input = |i: usize| inputs.get(i)
+            .map_or(&silence[..], |c| &c[..nframes]);
+
+        let mut left = vec![0.0; nframes];
+        let mut right = vec![0.0; nframes];
+
+        self.process([input(0), input(1)], [&mut left, &mut right], nframes);
+
+        let mut outputs = vec![left, right];
+        outputs.truncate(P::OUTPUT_CHANNELS);
+        outputs
+    }
+
+    // the events the plugin emitted during the last `process()`, in frame order.
+    #[inline]
+    pub fn drain_output_events(&mut self) -> impl Iterator<Item = Event<P>> + '_ {
+        self.wrapped.drain_output_events()
+    }
+}
+
+struct InstanceHost<'a> {
+    sample_rate: f32,
+    musical_time: &'a MusicalTime
+}
+
+impl<'a> HostContext for InstanceHost<'a> {
+    fn get_sample_rate(&self) -> Option<f32> {
+        Some(self.sample_rate)
+    }
+
+    fn get_block_size(&self) -> Option<usize> {
+        None
+    }
+
+    fn get_tempo(&self) -> Option<f64> {
+        Some(self.musical_time.bpm)
+    }
+
+    fn automate(&self, _index: usize, _value: f32) {
+    }
+
+    fn report_latency_changed(&self, _samples: usize) {
+    }
+
+    fn get_vendor_string(&self) -> Option<String> {
+        None
+    }
+}
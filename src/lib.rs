@@ -11,7 +11,8 @@ mod smooth;
 pub use smooth::{
     Smooth,
     SmoothOutput,
-    SmoothStatus
+    SmoothStatus,
+    SmoothDomain
 };
 
 mod declick;
@@ -20,6 +21,21 @@ pub use declick::{
     DeclickOutput
 };
 
+mod delay;
+pub use delay::DelayLine;
+
+mod meter;
+pub use meter::{
+    ClipMeter,
+    ClipIndicator
+};
+
+mod swap;
+pub use swap::{
+    SampleSwap,
+    SampleLoader
+};
+
 pub mod event;
 pub use event::Event;
 
@@ -29,14 +45,22 @@ pub use model::*;
 pub mod parameter;
 pub use parameter::Param;
 
+pub mod preset;
+
 mod plugin;
 pub use plugin::*;
 
 mod time;
 pub use time::*;
 
+mod host;
+pub use host::HostContext;
+
 mod wrapper;
 
+mod instance;
+pub use instance::Instance;
+
 pub use baseplug_derive::model;
 
 
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// a clip detector for driving a clip LED. the `ClipMeter` half lives on the audio thread and
+// watches the output, the `ClipIndicator` half goes to the UI.
+//
+// the indicator lights up as soon as a sample leaves `-1.0..=1.0` (or isn't finite), and goes out
+// again once `hold_ms` have passed without one.
+pub struct ClipMeter {
+    clipped: Arc<AtomicBool>,
+
+    hold_ms: f32,
+    hold_frames: usize,
+    remaining: usize
+}
+
+#[derive(Clone)]
+pub struct ClipIndicator {
+    clipped: Arc<AtomicBool>
+}
+
+impl ClipMeter {
+    pub fn new(sample_rate: f32, hold_ms: f32) -> (Self, ClipIndicator) {
+        let clipped = Arc::new(AtomicBool::new(false));
+
+        let meter = Self {
+            clipped: clipped.clone(),
+
+            hold_ms,
+            hold_frames: hold_frames(sample_rate, hold_ms),
+            remaining: 0
+        };
+
+        (meter, ClipIndicator { clipped })
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.hold_frames = hold_frames(sample_rate, self.hold_ms);
+        self.remaining = self.remaining.min(self.hold_frames);
+    }
+
+    // realtime-safe. `channels` all hold the same number of frames.
+    pub fn process<B: AsRef<[f32]>>(&mut self, channels: &[B]) {
+        let nframes = match channels.first() {
+            Some(channel) => channel.as_ref().len(),
+            None => return
+        };
+
+        let clipped = channels.iter()
+            .flat_map(|channel| channel.as_ref().iter())
+            .any(|x| x.abs() > 1.0 || x.is_nan());
+
+        if clipped {
+            self.remaining = self.hold_frames;
+            self.clipped.store(true, Ordering::Relaxed);
+        } else {
+            self.remaining = self.remaining.saturating_sub(nframes);
+
+            if self.remaining == 0 {
+                self.clipped.store(false, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn indicator(&self) -> ClipIndicator {
+        ClipIndicator {
+            clipped: self.clipped.clone()
+        }
+    }
+}
+
+impl ClipIndicator {
+    #[inline]
+    pub fn is_clipping(&self) -> bool {
+        self.clipped.load(Ordering::Relaxed)
+    }
+}
+
+#[inline]
+fn hold_frames(sample_rate: f32, hold_ms: f32) -> usize {
+    (hold_ms * (sample_rate / 1000.0)).max(0.0) as usize
+}
@@ -5,6 +5,9 @@ pub trait Model<P: Plugin>: Sized + Default + 'static {
     type Smooth:
         SmoothModel<P, Self>
         + Parameters<P, Self::Smooth>;
+
+    const PARAM_COUNT: usize =
+        <Self::Smooth as Parameters<P, Self::Smooth>>::PARAMS.len();
 }
 
 pub trait SmoothModel<P: Plugin, T: Model<P>>: Sized + 'static{
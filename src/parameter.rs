@@ -8,9 +8,25 @@ use crate::util::*;
 pub enum Gradient {
     Linear,
     Power(f32),
-    Exponential
+    Exponential,
+
+    // like `Exponential`, slow at the bottom of the range and fast at the top, but fine with a
+    // `min` of zero. see `LOG_CURVE` for its shape.
+    Logarithmic,
+
+    // `from_normalised` maps the host's normalised value onto a 0..1 position within the
+    // parameter's range, and `to_normalised` is its inverse.
+    Custom {
+        from_normalised: fn(f32) -> f32,
+        to_normalised: fn(f32) -> f32
+    }
 }
 
+// the steepness of `Gradient::Logarithmic`: the unit range is mapped onto the normalised one
+// through `ln(1 + LOG_CURVE * x) / ln(1 + LOG_CURVE)`. at this value, half-way up the normalised
+// range is about 9% of the way up the parameter's, like an audio taper pot.
+pub const LOG_CURVE: f32 = 100.0;
+
 #[derive(Debug)]
 pub enum Type {
     Numeric {
@@ -20,6 +36,12 @@ pub enum Type {
         gradient: Gradient
     },
 
+    // on or off, stored as `max` or `min`. normalised values from 0.5 up switch it on.
+    Switch {
+        min: f32,
+        max: f32
+    }
+
     // eventually will have an Enum/Discrete type here
 }
 
@@ -27,11 +49,26 @@ pub enum Type {
 pub enum Unit {
     Generic,
     Decibels,
-    Percentage
+    Percentage,
+
+    // any other unit, written after the value in its display string (e.g. ":1" for a ratio).
+    Custom(&'static str)
+}
+
+// which representation a model field holds its parameter's value in. this only makes a
+// difference for units that have a separate DSP representation, i.e. `Decibels`.
+#[derive(Debug)]
+pub enum Storage {
+    // the value as the DSP uses it, e.g. a gain coefficient for a `Decibels` parameter.
+    Dsp,
+
+    // the value in the parameter's unit, e.g. decibels. convert it yourself in `process()`.
+    Unit
 }
 
 pub struct Format<P: Plugin, Model> {
-    pub display_cb: fn(&Param<P, Model>, &Model, &mut dyn io::Write) -> io::Result<()>,
+    // formats a value as stored in the model, see `Param::storage`.
+    pub display_cb: fn(&Param<P, Model>, f32, &mut dyn io::Write) -> io::Result<()>,
     pub label: &'static str
 }
 
@@ -39,15 +76,40 @@ pub struct Param<P: Plugin, Model> {
     pub name: &'static str,
     pub short_name: Option<&'static str>,
 
+    // position in `Parameters::PARAMS`. this is how parameters are told apart, since the
+    // addresses of a constant's contents aren't guaranteed to be the same everywhere it's used.
+    pub index: usize,
+
     pub unit: Unit,
+    pub storage: Storage,
+
+    // flips the host-facing normalised range, so that 0.0 maps to `max` and 1.0 to `min`.
+    pub inverted: bool,
+
+    // still shown to the user, but the host won't record or play back automation for it.
+    pub automatable: bool,
+
+    // springs back to its default from the block after the one it was set in, e.g. for trigger or
+    // tap buttons. the host and UI are told about the return to default.
+    pub momentary: bool,
 
     pub param_type: Type,
     pub format: Format<P, Model>,
 
     pub dsp_notify: Option<fn(&mut P)>,
 
+    // `dsp_notify` is skipped until the normalised value has moved at least this far from
+    // where it was when the plugin was last notified.
+    pub notify_threshold: Option<f32>,
+
     pub set_cb: fn(&Param<P, Model>, &mut Model, f32),
-    pub get_cb: fn(&Param<P, Model>, &Model) -> f32
+    pub get_cb: fn(&Param<P, Model>, &Model) -> f32,
+
+    // the field's value as stored in the model, rather than normalised.
+    pub value_cb: fn(&Model) -> f32,
+
+    // always `Inactive` for fields that aren't wrapped in `Smooth` or `Declick`.
+    pub status_cb: fn(&Model) -> SmoothStatus
 }
 
 impl<P: Plugin, Model> Param<P, Model> {
@@ -61,6 +123,33 @@ impl<P: Plugin, Model> Param<P, Model> {
         (self.get_cb)(self, model)
     }
 
+    #[inline]
+    pub fn status(&self, model: &Model) -> SmoothStatus {
+        (self.status_cb)(model)
+    }
+
+    // maps a normalised value to the value in the parameter's unit, the "plain" value that hosts
+    // show and automate in. this is decibels for a `Decibels` parameter even when the model
+    // stores a gain coefficient.
+    pub fn normalised_to_unit(&self, normalised: f32) -> f32 {
+        let val = f32::xlate_in(self, normalised);
+
+        match (&self.unit, &self.storage) {
+            (Unit::Decibels, Storage::Dsp) => coeff_to_db(val),
+            _ => val
+        }
+    }
+
+    // the inverse of `normalised_to_unit()`.
+    pub fn unit_to_normalised(&self, val: f32) -> f32 {
+        let val = match (&self.unit, &self.storage) {
+            (Unit::Decibels, Storage::Dsp) => db_to_coeff(val),
+            _ => val
+        };
+
+        val.xlate_out(self)
+    }
+
     #[inline]
     pub fn get_name(&self) -> &'static str {
         self.short_name
@@ -73,6 +162,9 @@ impl<P: Plugin, Model> Param<P, Model> {
             "dB"
         } else if let Unit::Percentage = self.unit {
             "%"
+        } else if let Unit::Custom(_) = self.unit {
+            // already part of the display string.
+            ""
         } else {
             self.format.label
         }
@@ -80,7 +172,13 @@ impl<P: Plugin, Model> Param<P, Model> {
 
     #[inline]
     pub fn get_display(&self, model: &Model, w: &mut dyn io::Write) -> io::Result<()> {
-        (self.format.display_cb)(self, model, w)
+        (self.format.display_cb)(self, (self.value_cb)(model), w)
+    }
+
+    // formats a normalised value the host asks about, e.g. for an automation lane tooltip, rather
+    // than the parameter's current one.
+    pub fn get_display_for(&self, normalised: f32, w: &mut dyn io::Write) -> io::Result<()> {
+        (self.format.display_cb)(self, f32::xlate_in(self, normalised), w)
     }
 }
 
@@ -89,7 +187,12 @@ impl<P: Plugin, Model> fmt::Debug for Param<P, Model> {
         f.debug_struct("Param")
             .field("name", &self.name)
             .field("short_name", &self.short_name)
+            .field("index", &self.index)
             .field("unit", &self.unit)
+            .field("storage", &self.storage)
+            .field("inverted", &self.inverted)
+            .field("automatable", &self.automatable)
+            .field("momentary", &self.momentary)
             .field("param_type", &self.param_type)
             .finish()
     }
@@ -102,18 +205,26 @@ pub trait Translatable<T, P: Plugin, Model> {
 
 impl<P: Plugin, Model> Translatable<f32, P, Model> for f32 {
     fn xlate_in(param: &Param<P, Model>, normalised: f32) -> f32 {
-        let (min, max, gradient) = match &param.param_type {
-            Type::Numeric { min, max, gradient } => (min, max, gradient)
+        let normalised = normalised.clamp(0.0, 1.0);
+        let normalised = if param.inverted {
+            1.0 - normalised
+        } else {
+            normalised
         };
 
-        let normalised = normalised.min(1.0).max(0.0);
+        let (min, max, gradient) = match &param.param_type {
+            Type::Numeric { min, max, gradient } => (min, max, gradient),
+
+            Type::Switch { min, max } =>
+                return if normalised >= 0.5 { *max } else { *min }
+        };
 
         let map = |x: f32| -> f32 {
             let range = max - min;
             let mapped = (x * range) + min;
 
-            match param.unit {
-                Unit::Decibels => db_to_coeff(mapped),
+            match (&param.unit, &param.storage) {
+                (Unit::Decibels, Storage::Dsp) => db_to_coeff(mapped),
                 _ => mapped
             }
         };
@@ -124,6 +235,18 @@ impl<P: Plugin, Model> Translatable<f32, P, Model> for f32 {
             Gradient::Power(exponent) =>
                 map(normalised.powf(*exponent)),
 
+            Gradient::Custom { from_normalised, .. } =>
+                map(from_normalised(normalised)),
+
+            Gradient::Logarithmic => {
+                // `exp_m1()` doesn't quite undo `ln_1p()`, so the top end is pinned to `max`.
+                if normalised == 1.0 {
+                    return map(1.0);
+                }
+
+                map((normalised * LOG_CURVE.ln_1p()).exp_m1() / LOG_CURVE)
+            },
+
             Gradient::Exponential => {
                 if normalised == 0.0 {
                     return *min;
@@ -141,40 +264,59 @@ impl<P: Plugin, Model> Translatable<f32, P, Model> for f32 {
     }
 
     fn xlate_out(&self, param: &Param<P, Model>) -> f32 {
-        let (min, max, gradient) = match &param.param_type {
-            Type::Numeric { min, max, gradient } => (min, max, gradient)
-        };
+        let normalised = xlate_out_uninverted(*self, param);
 
-        if *self <= *min {
-            return 0.0;
+        if param.inverted {
+            1.0 - normalised
+        } else {
+            normalised
         }
+    }
+}
 
-        if *self >= *max {
-            return 1.0;
-        }
+fn xlate_out_uninverted<P: Plugin, Model>(val: f32, param: &Param<P, Model>) -> f32 {
+    let (min, max, gradient) = match &param.param_type {
+        Type::Numeric { min, max, gradient } => (min, max, gradient),
 
-        let unmap = |x: f32| -> f32 {
-            let range = max - min;
+        Type::Switch { min, max } =>
+            return if val >= (min + max) / 2.0 { 1.0 } else { 0.0 }
+    };
 
-            let x = match param.unit {
-                Unit::Decibels => coeff_to_db(x),
-                _ => x
-            };
+    // the range is in decibels, whichever way the value is stored.
+    let val = match (&param.unit, &param.storage) {
+        (Unit::Decibels, Storage::Dsp) => coeff_to_db(val),
+        _ => val
+    };
 
-            (x - min) / range
-        };
+    if val <= *min {
+        return 0.0;
+    }
 
-        match gradient {
-            Gradient::Linear => unmap(*self),
+    if val >= *max {
+        return 1.0;
+    }
 
-            Gradient::Power(exponent) =>
-                unmap(*self).powf(1.0 / *exponent),
+    let unmap = |x: f32| -> f32 {
+        let range = max - min;
+        (x - min) / range
+    };
 
-            Gradient::Exponential => {
-                let minl = min.log2();
-                let range = max.log2() - minl;
-                (self.log2() - minl) / range
-            }
+    match gradient {
+        Gradient::Linear => unmap(val),
+
+        Gradient::Power(exponent) =>
+            unmap(val).powf(1.0 / *exponent),
+
+        Gradient::Custom { to_normalised, .. } =>
+            to_normalised(unmap(val)),
+
+        Gradient::Logarithmic =>
+            (unmap(val) * LOG_CURVE).ln_1p() / LOG_CURVE.ln_1p(),
+
+        Gradient::Exponential => {
+            let minl = min.log2();
+            let range = max.log2() - minl;
+            (val.log2() - minl) / range
         }
     }
 }
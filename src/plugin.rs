@@ -10,6 +10,9 @@ use crate::parameter::*;
 use crate::event::*;
 use crate::model::*;
 use crate::time::*;
+use crate::host::*;
+use crate::util::midi_status;
+use crate::meter::ClipMeter;
 
 
 pub struct AudioBus<'a> {
@@ -31,7 +34,51 @@ pub struct ProcessContext<'a, 'b, P: Plugin> {
 
     pub enqueue_event: &'a mut dyn FnMut(Event<P>),
 
-    pub musical_time: &'a MusicalTime
+    pub musical_time: &'a MusicalTime,
+
+    pub host: &'a dyn HostContext,
+
+    pub(crate) instance_seed: u64
+}
+
+impl<'a, 'b, P: Plugin> ProcessContext<'a, 'b, P> {
+    #[inline]
+    pub fn input_bus_count(&self) -> usize {
+        self.inputs.len()
+    }
+
+    #[inline]
+    pub fn output_bus_count(&self) -> usize {
+        self.outputs.len()
+    }
+
+    // number of channels connected on an input bus, 0 if there's no such bus.
+    #[inline]
+    pub fn input_channels(&self, bus: usize) -> usize {
+        self.inputs.get(bus)
+            .map_or(0, |b| b.connected_channels.max(0) as usize)
+    }
+
+    // number of channels connected on an output bus, 0 if there's no such bus.
+    #[inline]
+    pub fn output_channels(&self, bus: usize) -> usize {
+        self.outputs.get(bus)
+            .map_or(0, |b| b.connected_channels.max(0) as usize)
+    }
+
+    // differs between plugin instances (even within the same host session) and stays the same for
+    // the lifetime of one. for seeding random number generators, so that e.g. several instances
+    // don't all start their LFOs at the same phase.
+    #[inline]
+    pub fn instance_seed(&self) -> u64 {
+        self.instance_seed
+    }
+
+    // see `HostContext::report_latency_changed()`.
+    #[inline]
+    pub fn report_latency_changed(&self, samples: usize) {
+        self.host.report_latency_changed(samples)
+    }
 }
 
 pub trait Parameters<P: Plugin, Model: 'static> {
@@ -52,18 +99,157 @@ pub trait Plugin: Sized + Send + Sync + 'static {
     const INPUT_CHANNELS: usize;
     const OUTPUT_CHANNELS: usize;
 
+    // hosts may hand us the same buffer for input and output. the two can't be borrowed at once,
+    // so the wrapper always gives the plugin a private copy of such an input, and reading input
+    // after writing output (e.g. a filter reading ahead) is safe either way. this no longer has
+    // any effect.
+    const ALLOW_IN_PLACE: bool = true;
+
+    // save only the parameters that differ from their defaults in the host's state chunk, see
+    // `preset::serialise_sparse()`. anything missing from a loaded chunk is taken from
+    // `Model::default()`.
+    const SPARSE_STATE: bool = false;
+
     type Model: Model<Self> + Serialize + DeserializeOwned;
 
     fn new(sample_rate: f32, model: &Self::Model) -> Self;
 
+    // called on state loaded from the host, before it is applied. preset data is untrusted, so
+    // this is the place to clamp values or repair invariants. `sample_rate` is 0.0 if the host
+    // hasn't set one yet.
+    fn validate_model(_model: &mut Self::Model, _sample_rate: f32) {
+    }
+
+    // for DSP that only works at particular rates. at any other rate, `new()` isn't called and
+    // the plugin outputs silence.
+    fn supports_sample_rate(_sample_rate: f32) -> bool {
+        true
+    }
+
+    // the host is about to start (or has just stopped) calling `process()`, e.g. around transport
+    // playback. distinct from construction via `new()`, which happens on sample rate changes and
+    // resets.
+    fn start_processing(&mut self) {
+    }
+
+    fn stop_processing(&mut self) {
+    }
+
+    // `process()` may be called several times per host buffer, since the wrapper splits buffers at
+    // event boundaries. these are called exactly once per host buffer, around all of the
+    // `process()` calls for it.
+    fn pre_process(&mut self, _nframes: usize, _musical_time: &MusicalTime) {
+    }
+
     fn process<'proc>(&mut self,
         model: &proc_model!(Self, 'proc),
         ctx: &'proc mut ProcessContext<Self>);
+
+    fn post_process(&mut self) {
+    }
+
+    // called from the audio thread for every parameter change, with the parameter's index (as in
+    // `Parameters::PARAMS`), its new normalised value and the frame within the host buffer that it
+    // takes effect at. unlike `dsp_notify`, this fires for each point of sample-accurate
+    // automation, without a threshold. `process()` is split at that frame, so the next call
+    // starts there.
+    //
+    // changes made before the plugin was constructed aren't reported, it's built with them in
+    // place.
+    fn on_param_sample(&mut self, _index: usize, _normalised: f32, _frame: usize) {
+    }
+
+    // observational, for logging or capturing automation: called with the same arguments once a
+    // parameter change has been applied and the plugin has reacted to it. host sets, automation,
+    // momentary releases and state loads all come through here, in the order they're applied.
+    // anything that changes the sound belongs in `dsp_notify` or `on_param_sample()`.
+    fn on_parameter_changed(&mut self, _index: usize, _normalised: f32, _frame: usize) {
+    }
+
+    // opt-in clip detection. if this returns a meter, the wrapper feeds it every host buffer of
+    // output, after all of the `process()` calls for it.
+    fn clip_meter(&mut self) -> Option<&mut ClipMeter> {
+        None
+    }
 }
 
 pub trait MidiReceiver: Plugin {
+    // when true, MIDI clock and song position pointer messages are decoded and delivered through
+    // `midi_clock()` and `song_position()` instead of `midi_input()`.
+    const DECODE_MIDI_SYNC: bool = false;
+
     fn midi_input<'proc>(&mut self, model: &proc_model!(Self, 'proc),
         data: [u8; 3]);
+
+    fn midi_clock<'proc>(&mut self, _model: &proc_model!(Self, 'proc)) {
+    }
+
+    fn song_position<'proc>(&mut self, _model: &proc_model!(Self, 'proc),
+        _position: u16) {
+    }
+
+    // silence every held voice. called on "all sound off" (CC 120) and "all notes off" (CC 123),
+    // after the message itself has gone through `midi_input()`, and when the host deactivates the
+    // plugin.
+    fn all_notes_off(&mut self) {
+    }
+
+    // a label for a MIDI note, shown by hosts in their piano roll. useful for drum plugins, e.g.
+    // "kick" for note 36.
+    fn midi_key_name(&self, _note: u8) -> Option<&str> {
+        None
+    }
+}
+
+// an alternative to implementing `MidiReceiver` directly, for plugins that would rather not decode
+// MIDI bytes themselves. every `NoteHandler` is a `MidiReceiver`.
+pub trait NoteHandler: Plugin {
+    fn note_on<'proc>(&mut self, model: &proc_model!(Self, 'proc),
+        note: u8, velocity: u8);
+
+    // also called for note ons with a velocity of 0, which many devices send instead.
+    fn note_off<'proc>(&mut self, _model: &proc_model!(Self, 'proc),
+        _note: u8, _velocity: u8) {
+    }
+
+    fn control_change<'proc>(&mut self, _model: &proc_model!(Self, 'proc),
+        _controller: u8, _value: u8) {
+    }
+
+    // every other MIDI message.
+    fn midi_input<'proc>(&mut self, _model: &proc_model!(Self, 'proc),
+        _data: [u8; 3]) {
+    }
+
+    // see `MidiReceiver::all_notes_off()`.
+    fn all_notes_off(&mut self) {
+    }
+
+    // see `MidiReceiver::midi_key_name()`.
+    fn midi_key_name(&self, _note: u8) -> Option<&str> {
+        None
+    }
+}
+
+impl<T: NoteHandler> MidiReceiver for T {
+    fn midi_input<'proc>(&mut self, model: &proc_model!(Self, 'proc),
+        data: [u8; 3])
+    {
+        match midi_status(&data) {
+            0x90 if data[2] > 0 => self.note_on(model, data[1], data[2]),
+            0x80 | 0x90 => self.note_off(model, data[1], data[2]),
+            0xB0 => self.control_change(model, data[1], data[2]),
+            _ => NoteHandler::midi_input(self, model, data)
+        }
+    }
+
+    fn all_notes_off(&mut self) {
+        NoteHandler::all_notes_off(self)
+    }
+
+    fn midi_key_name(&self, note: u8) -> Option<&str> {
+        NoteHandler::midi_key_name(self, note)
+    }
 }
 
 pub type WindowOpenResult<T> = Result<T, ()>;
@@ -78,4 +264,15 @@ pub trait PluginUI: Plugin {
 
     fn ui_param_notify(handle: &Self::Handle,
         param: &'static Param<Self, <Self::Model as Model<Self>>::Smooth>, val: f32);
+
+    // called periodically from the host's editor idle loop, independent of whether audio is
+    // being processed. use this to pump any pending UI messages, or to query the host for things
+    // like tempo while the transport is stopped.
+    fn ui_idle(_handle: &Self::Handle, _host: &dyn HostContext) {
+    }
+
+    // called when the editor opens, and whenever the host changes the sample rate or maximum
+    // block size while it's open, for display. `max_block_size` is `None` until the host tells us.
+    fn ui_host_info(_handle: &Self::Handle, _sample_rate: f32, _max_block_size: Option<usize>) {
+    }
 }
@@ -0,0 +1,114 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::{
+    Serialize,
+    Deserialize,
+    de::DeserializeOwned
+};
+
+use serde_json::Value;
+
+// bump this whenever the envelope layout changes. models themselves are versioned by whatever
+// serde attributes the plugin puts on them.
+const BANK_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct BankOut<'a, M> {
+    version: u32,
+    presets: &'a [M]
+}
+
+#[derive(Deserialize)]
+struct BankIn<M> {
+    version: u32,
+    presets: Vec<M>
+}
+
+pub fn save_bank<M: Serialize>(path: impl AsRef<Path>, presets: &[M]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+
+    let bank = BankOut {
+        version: BANK_VERSION,
+        presets
+    };
+
+    serde_json::to_writer_pretty(&mut out, &bank)?;
+
+    // dropping the writer would flush it too, but throw away any error on the way.
+    out.flush()?;
+    Ok(())
+}
+
+pub fn load_bank<M: DeserializeOwned>(path: impl AsRef<Path>) -> io::Result<Vec<M>> {
+    let input = BufReader::new(File::open(path)?);
+    let bank: BankIn<M> = serde_json::from_reader(input)?;
+
+    if bank.version > BANK_VERSION {
+        return Err(io::Error::new(io::ErrorKind::InvalidData,
+            format!("preset bank version {} is newer than supported version {}",
+                bank.version, BANK_VERSION)));
+    }
+
+    Ok(bank.presets)
+}
+
+// serialises only the fields of `model` that differ from `M::default()`, so that a preset at
+// defaults is (nearly) empty. read it back with `deserialise_sparse()`.
+pub fn serialise_sparse<M: Serialize + Default>(model: &M) -> serde_json::Result<Vec<u8>> {
+    let mut value = serde_json::to_value(model)?;
+    let default = serde_json::to_value(M::default())?;
+
+    strip_defaults(&mut value, &default);
+    serde_json::to_vec(&value)
+}
+
+// fields missing from `data` are taken from `M::default()`, so this also reads the output of a
+// plain full serialisation.
+pub fn deserialise_sparse<M>(data: &[u8]) -> serde_json::Result<M>
+    where M: Serialize + DeserializeOwned + Default
+{
+    let value: Value = serde_json::from_slice(data)?;
+    let mut model = serde_json::to_value(M::default())?;
+
+    fill_defaults(&mut model, value);
+    serde_json::from_value(model)
+}
+
+fn strip_defaults(value: &mut Value, default: &Value) {
+    let (fields, defaults) = match (value, default) {
+        (Value::Object(f), Value::Object(d)) => (f, d),
+        _ => return
+    };
+
+    fields.retain(|key, field| {
+        match defaults.get(key) {
+            Some(d) if d == field => false,
+
+            Some(d) => {
+                strip_defaults(field, d);
+                true
+            },
+
+            None => true
+        }
+    });
+}
+
+fn fill_defaults(model: &mut Value, value: Value) {
+    let (fields, values) = match (model, value) {
+        (Value::Object(f), Value::Object(v)) => (f, v),
+        (model, value) => {
+            *model = value;
+            return
+        }
+    };
+
+    for (key, value) in values {
+        match fields.get_mut(&key) {
+            Some(field) => fill_defaults(field, value),
+            None => { fields.insert(key, value); }
+        }
+    }
+}
@@ -22,7 +22,13 @@ impl SmoothStatus {
 
 pub struct SmoothOutput<'a, T> {
     pub values: &'a [T],
-    pub status: SmoothStatus
+    pub status: SmoothStatus,
+
+    // the value being smoothed towards, see `dest_value()`.
+    pub dest: T,
+
+    // see `changed()`.
+    pub changed: bool
 }
 
 impl<'a, T> SmoothOutput<'a, T> {
@@ -30,6 +36,23 @@ impl<'a, T> SmoothOutput<'a, T> {
     pub fn is_smoothing(&self) -> bool {
         self.status.is_active()
     }
+
+    // whether this block ends on a different value than the previous one did, i.e. whether
+    // anything derived from the values needs recomputing. unlike `is_smoothing()`, this also
+    // catches jumps from a `reset()`, and is false once the ramp has stopped moving.
+    #[inline]
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+}
+
+impl<'a, T: Copy> SmoothOutput<'a, T> {
+    // the target value, i.e. what `values` settle at. for setup code that needs one value for
+    // the whole block rather than a per-frame one.
+    #[inline]
+    pub fn dest_value(&self) -> T {
+        self.dest
+    }
 }
 
 impl<'a, T, I> ops::Index<I> for SmoothOutput<'a, T>
@@ -43,6 +66,22 @@ impl<'a, T, I> ops::Index<I> for SmoothOutput<'a, T>
     }
 }
 
+// a pair of conversions that make a `Smooth` ramp in a different domain than the values it is fed
+// and hands out, e.g. smoothing a gain coefficient in decibels. `into` maps a value into the
+// smoothing domain, `from` maps it back.
+pub struct SmoothDomain<T> {
+    pub into: fn(T) -> T,
+    pub from: fn(T) -> T
+}
+
+impl<T> Clone for SmoothDomain<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SmoothDomain<T> {}
+
 pub struct Smooth<T: Float> {
     output: [T; crate::MAX_BLOCKSIZE],
     input: T,
@@ -51,49 +90,98 @@ pub struct Smooth<T: Float> {
 
     a: T,
     b: T,
-    last_output: T
+    last_output: T,
+
+    // what the previous `process()` ended on, and whether the latest one ended elsewhere.
+    block_end: T,
+    changed: bool,
+
+    // `input` and `state` are in the smoothing domain, everything else is not.
+    domain: Option<SmoothDomain<T>>,
+    state: T
 }
 
 impl<T> Smooth<T>
     where T: Float + fmt::Display
 {
     pub fn new(input: T) -> Self {
+        Self::new_in(input, None)
+    }
+
+    pub fn with_domain(input: T, domain: SmoothDomain<T>) -> Self {
+        Self::new_in(input, Some(domain))
+    }
+
+    fn new_in(input: T, domain: Option<SmoothDomain<T>>) -> Self {
+        let state = domain.map_or(input, |d| (d.into)(input));
+
         Self {
             status: SmoothStatus::Inactive,
-            input,
+            input: state,
             output: [input; crate::MAX_BLOCKSIZE],
 
             a: T::one(),
             b: T::zero(),
-            last_output: input
+            last_output: input,
+
+            block_end: input,
+            changed: false,
+
+            domain,
+            state
         }
     }
 
+    #[inline]
+    fn domain_in(&self, val: T) -> T {
+        self.domain.map_or(val, |d| (d.into)(val))
+    }
+
+    #[inline]
+    fn domain_out(&self, val: T) -> T {
+        self.domain.map_or(val, |d| (d.from)(val))
+    }
+
     pub fn reset(&mut self, val: T)
     {
         *self = Self {
             a: self.a,
             b: self.b,
 
-            ..Self::new(val)
+            block_end: self.block_end,
+            changed: self.changed,
+
+            ..Self::new_in(val, self.domain)
         };
     }
 
     pub fn set(&mut self, val: T) {
-        self.input = val;
+        self.input = self.domain_in(val);
         self.status = SmoothStatus::Active;
     }
 
     #[inline]
     pub fn dest(&self) -> T {
-        self.input
+        self.domain_out(self.input)
+    }
+
+    // the value that the next `process()` call will produce at frame 0, without running it.
+    #[inline]
+    pub fn peek(&self) -> T {
+        if self.status == SmoothStatus::Active {
+            self.domain_out((self.input * self.a) + (self.state * self.b))
+        } else {
+            self.output[0]
+        }
     }
 
     #[inline]
     pub fn output(&self) -> SmoothOutput<T> {
         SmoothOutput {
             values: &self.output,
-            status: self.status
+            status: self.status,
+            dest: self.dest(),
+            changed: self.changed
         }
     }
 
@@ -101,7 +189,9 @@ impl<T> Smooth<T>
     pub fn current_value(&self) -> SmoothOutput<T> {
         SmoothOutput {
             values: slice::from_ref(&self.last_output),
-            status: self.status
+            status: self.status,
+            dest: self.dest(),
+            changed: self.changed
         }
     }
 
@@ -110,8 +200,9 @@ impl<T> Smooth<T>
 
         match status {
             SmoothStatus::Active => {
-                if (self.input - self.output[0]).abs() < epsilon {
-                    self.reset(self.input);
+                if (self.input - self.state).abs() < epsilon {
+                    let dest = self.dest();
+                    self.reset(dest);
                     self.status = SmoothStatus::Deactivating;
                 }
             },
@@ -126,17 +217,33 @@ impl<T> Smooth<T>
     }
 
     pub fn process(&mut self, nframes: usize) {
-        if self.status != SmoothStatus::Active {
-            return
+        if self.status == SmoothStatus::Active {
+            self.ramp(nframes);
         }
 
+        // ramps only ever move one way, and outside of one every value equals `last_output`, so
+        // the last value is all there is to compare.
+        self.changed = self.last_output != self.block_end;
+        self.block_end = self.last_output;
+    }
+
+    fn ramp(&mut self, nframes: usize) {
         let nframes = nframes.min(crate::MAX_BLOCKSIZE);
         let input = self.input * self.a;
 
-        self.output[0] = input + (self.last_output * self.b);
+        let mut state = self.state;
 
-        for i in 1..nframes {
-            self.output[i] = input + (self.output[i - 1] * self.b);
+        for out in self.output[..nframes].iter_mut() {
+            state = input + (state * self.b);
+            *out = state;
+        }
+
+        self.state = state;
+
+        if let Some(domain) = self.domain {
+            for out in self.output[..nframes].iter_mut() {
+                *out = (domain.from)(*out);
+            }
         }
 
         self.last_output = self.output[nframes - 1];
@@ -146,9 +253,16 @@ impl<T> Smooth<T>
     pub fn is_active(&self) -> bool {
         self.status.is_active()
     }
+
+    #[inline]
+    pub fn status(&self) -> SmoothStatus {
+        self.status
+    }
 }
 
 impl Smooth<f32> {
+    // `ms` is the one-pole's time constant, not the time it takes to settle: after `ms`, the
+    // output has covered 1 - 1/e (about 63%) of the distance to its target.
     pub fn set_speed_ms(&mut self, sample_rate: f32, ms: f32) {
         self.b = (-1.0f32 / (ms * (sample_rate / 1000.0f32))).exp();
         self.a = 1.0f32 - self.b;
@@ -189,6 +303,7 @@ impl<T> fmt::Debug for Smooth<T>
             .field("input", &self.input)
             .field("status", &self.status)
             .field("last_output", &self.last_output)
+            .field("state", &self.state)
             .finish()
     }
 }
@@ -0,0 +1,108 @@
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+// hands large, expensively-built values (impulse responses, samples, wavetables) from a
+// non-realtime thread to `process()`.
+//
+// the `SampleLoader` half lives on the UI (or a worker) thread, which does the loading and all of
+// the allocating and freeing. the `SampleSwap` half lives in the plugin and picks up the most
+// recently loaded value with `update()`, which never blocks, allocates or frees. the value it
+// replaces is handed back to the loader, to be dropped on the next `load()` or `collect()`.
+pub struct SampleSwap<T> {
+    slots: Arc<Slots<T>>,
+    current: Option<Box<T>>
+}
+
+pub struct SampleLoader<T> {
+    slots: Arc<Slots<T>>
+}
+
+struct Slots<T> {
+    // loaded, not yet picked up by `process()`.
+    pending: AtomicPtr<T>,
+
+    // swapped out by `process()`, waiting to be dropped by the loader.
+    retired: AtomicPtr<T>,
+
+    _value: PhantomData<Box<T>>
+}
+
+impl<T> SampleSwap<T> {
+    pub fn new() -> (Self, SampleLoader<T>) {
+        let slots = Arc::new(Slots {
+            pending: AtomicPtr::new(ptr::null_mut()),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            _value: PhantomData
+        });
+
+        let swap = Self {
+            slots: slots.clone(),
+            current: None
+        };
+
+        (swap, SampleLoader { slots })
+    }
+
+    // realtime-safe. swaps in the latest loaded value, if there is one, and returns whether it
+    // did. while the loader hasn't collected the previously replaced value yet, the swap is put
+    // off until a later call.
+    pub fn update(&mut self) -> bool {
+        let slots = &*self.slots;
+
+        if !slots.retired.load(Ordering::Acquire).is_null() {
+            return false;
+        }
+
+        let next = slots.pending.swap(ptr::null_mut(), Ordering::AcqRel);
+
+        if next.is_null() {
+            return false;
+        }
+
+        let next = unsafe { Box::from_raw(next) };
+
+        if let Some(prev) = self.current.replace(next) {
+            slots.retired.store(Box::into_raw(prev), Ordering::Release);
+        }
+
+        true
+    }
+
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        self.current.as_deref()
+    }
+}
+
+impl<T> SampleLoader<T> {
+    // not realtime-safe. replaces any value that `process()` hasn't picked up yet.
+    pub fn load(&self, value: T) {
+        let value = Box::into_raw(Box::new(value));
+        let superseded = self.slots.pending.swap(value, Ordering::AcqRel);
+
+        drop_raw(superseded);
+        self.collect();
+    }
+
+    // not realtime-safe. drops the value `process()` most recently swapped out, if any. call this
+    // periodically (e.g. from `PluginUI::ui_idle()`) so that `SampleSwap::update()` isn't held
+    // up.
+    pub fn collect(&self) {
+        drop_raw(self.slots.retired.swap(ptr::null_mut(), Ordering::AcqRel));
+    }
+}
+
+impl<T> Drop for Slots<T> {
+    fn drop(&mut self) {
+        drop_raw(*self.pending.get_mut());
+        drop_raw(*self.retired.get_mut());
+    }
+}
+
+fn drop_raw<T>(value: *mut T) {
+    if !value.is_null() {
+        drop(unsafe { Box::from_raw(value) });
+    }
+}
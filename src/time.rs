@@ -6,6 +6,33 @@ pub struct MusicalTime {
 }
 
 impl MusicalTime {
+    #[inline]
+    pub fn samples_per_beat(&self, sample_rate: f64) -> f64 {
+        (60f64 / self.bpm) * sample_rate
+    }
+
+    // the first multiple of `division` beats (1.0 for every beat, 0.25 for sixteenths, ...) at or
+    // after the start of the block, as `(beat, frame)`. a boundary that falls between two samples
+    // belongs to the later one, so consecutive blocks neither skip nor repeat a boundary. check
+    // the frame against the block length, the boundary may well be in a later block.
+    pub fn next_beat_boundary(&self, division: f64, sample_rate: f64) -> (f64, usize) {
+        let one_sample = 1.0 / self.samples_per_beat(sample_rate);
+        let beat = (((self.beat - one_sample) / division).floor() + 1.0) * division;
+
+        (beat, self.frame_of_beat(beat, sample_rate))
+    }
+
+    // the first frame of the block at or after `beat`, 0 if it has already passed. the block's
+    // position has usually been added up block by block, so a beat within a rounding error of a
+    // frame lands on that frame rather than the one after.
+    pub fn frame_of_beat(&self, beat: f64, sample_rate: f64) -> usize {
+        let frames = (beat - self.beat) * self.samples_per_beat(sample_rate);
+
+        (frames - 1e-3)
+            .ceil()
+            .max(0.0) as usize
+    }
+
     pub(crate) fn step_by_samples(&mut self, sample_rate: f64, samples: usize) {
         let beats_per_second = self.bpm / 60f64;
         let seconds = (samples as f64) / (sample_rate as f64);
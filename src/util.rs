@@ -31,3 +31,50 @@ pub fn coeff_to_db(coeff: f32) -> f32 {
         20.0 * coeff.log(10.0)
     }
 }
+
+// clamps `x` to `[min, max]`, mapping NaN to `min` and infinities to the nearest bound. handy for
+// sanitising values on their way into `tan()`, `ln()` and friends.
+#[inline]
+pub fn clamp_finite(x: f32, min: f32, max: f32) -> f32 {
+    if x.is_nan() {
+        min
+    } else {
+        x.max(min).min(max)
+    }
+}
+
+// about -360dB. far below anything audible, and below the precision of any signal near full
+// scale, but far above the largest denormal.
+pub const ANTI_DENORMAL: f32 = 1e-18;
+
+// nudges a denormal (or zero) into the normal range by adding a tiny DC offset, for feedback
+// paths (filter states, reverb tails) on platforms without flush-to-zero. values of audible
+// magnitude come back unchanged.
+#[inline]
+pub fn prevent_denormals(sample: f32) -> f32 {
+    sample + ANTI_DENORMAL
+}
+
+// `prevent_denormals()` for a whole block, alternating the sign of the offset from sample to
+// sample so that it doesn't add up to DC.
+pub fn add_antidenormal(block: &mut [f32]) {
+    for pair in block.chunks_mut(2) {
+        pair[0] += ANTI_DENORMAL;
+
+        if let Some(odd) = pair.get_mut(1) {
+            *odd -= ANTI_DENORMAL;
+        }
+    }
+}
+
+// the status nibble of a channel message, e.g. 0x90 for a note on.
+#[inline]
+pub fn midi_status(data: &[u8; 3]) -> u8 {
+    data[0] & 0xF0
+}
+
+// the zero-based channel (0..=15) a channel message is addressed to.
+#[inline]
+pub fn midi_channel(data: &[u8; 3]) -> u8 {
+    data[0] & 0x0F
+}
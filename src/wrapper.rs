@@ -1,6 +1,12 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use crate::{
     Model,
     SmoothModel,
+    Parameters,
 
     Plugin,
     PluginUI,
@@ -11,13 +17,20 @@ use crate::{
     AudioBusMut,
     ProcessContext,
     MusicalTime,
+    HostContext,
+    SmoothStatus,
 
     Event,
     event
 };
 
+pub(crate) const DEFAULT_EVENT_CAPACITY: usize = 512;
+pub(crate) const DEFAULT_OUTPUT_EVENT_CAPACITY: usize = 256;
+
 pub(crate) struct WrappedPlugin<P: Plugin> {
-    pub(crate) plug: P,
+    // not constructed until the host has told us the sample rate, so that `Plugin::new()` only
+    // ever runs at the real rate. until then, we don't let anything near audio.
+    plug: Option<P>,
 
     // even though it is *strongly forbidden* to allocate in the RT audio thread, many plugin APIs
     // have no facilities for host-side allocation of event buffers which live through the
@@ -26,16 +39,43 @@ pub(crate) struct WrappedPlugin<P: Plugin> {
     // the best we can do is pre-allocate a reasonably large buffer and hope we never have to
     // enlarge it.
     //
-    // see below in WrappedPlugin::new() for the capacity.
+    // see DEFAULT_EVENT_CAPACITY and DEFAULT_OUTPUT_EVENT_CAPACITY above for the capacities.
     //
     // XXX: there are *potential* threading issues with this. it would be completely possible for
     // an enqueue_event() call to come *during* a process() call, and we need to be able to handle
     // that in the future. we may need to use a different data structure here.
     events: Vec<Event<P>>,
-    pub(crate) output_events: Vec<Event<P>>,
+    output_events: Vec<Event<P>>,
 
     pub(crate) smoothed_model: <P::Model as Model<P>>::Smooth,
     sample_rate: f32,
+    max_block_size: Option<usize>,
+
+    // see `ProcessContext::instance_seed()`.
+    instance_seed: u64,
+
+    // normalised values that the host has set but that haven't been applied yet, indexed like
+    // `Parameters::PARAMS`. see `set_parameter()`.
+    pending_sets: Vec<Option<f32>>,
+
+    // normalised value of each parameter as of its last `dsp_notify` call, indexed like
+    // `Parameters::PARAMS`. see `Param::notify_threshold`.
+    last_notified: Vec<f32>,
+
+    // parameters whose new value the UI hasn't been told about yet, indexed like
+    // `Parameters::PARAMS`. host automation can set the same parameter many times per block, so
+    // these are collected and flushed once per `process()` (and on editor idle, in case audio
+    // isn't running).
+    ui_dirty: Vec<bool>,
+
+    // normalised default of each parameter, indexed like `Parameters::PARAMS`.
+    param_defaults: Vec<f32>,
+
+    // private copy of inputs the host handed us in place, see `InputChannel::Output`.
+    input_scratch: [[f32; crate::MAX_BLOCKSIZE]; 2],
+
+    // stands in for output channels the host didn't provide.
+    output_scratch: [[f32; crate::MAX_BLOCKSIZE]; 2],
 
     pub(crate) ui_handle: Option<<Self as WrappedPluginUI<P>>::UIHandle>
 }
@@ -43,13 +83,37 @@ pub(crate) struct WrappedPlugin<P: Plugin> {
 impl<P: Plugin> WrappedPlugin<P> {
     #[inline]
     pub(crate) fn new() -> Self {
+        Self::with_capacity(DEFAULT_EVENT_CAPACITY, DEFAULT_OUTPUT_EVENT_CAPACITY)
+    }
+
+    pub(crate) fn with_capacity(event_capacity: usize, output_event_capacity: usize) -> Self {
+        let smoothed_model =
+            <P::Model as Model<P>>::Smooth::from_model(P::Model::default());
+
+        let param_defaults: Vec<f32> = <P::Model as Model<P>>::Smooth::PARAMS.iter()
+            .map(|param| param.get(&smoothed_model))
+            .collect();
+
+        let last_notified = param_defaults.clone();
+
+        let pending_sets = vec![None; <P::Model as Model<P>>::PARAM_COUNT];
+        let ui_dirty = vec![false; <P::Model as Model<P>>::PARAM_COUNT];
+
         Self {
-            plug: P::new(48000.0, &P::Model::default()),
-            events: Vec::with_capacity(512),
-            output_events: Vec::with_capacity(256),
-            smoothed_model:
-                <P::Model as Model<P>>::Smooth::from_model(P::Model::default()),
+            plug: None,
+            events: Vec::with_capacity(event_capacity),
+            output_events: Vec::with_capacity(output_event_capacity),
+            smoothed_model,
             sample_rate: 0.0,
+            max_block_size: None,
+            instance_seed: next_instance_seed(),
+            pending_sets,
+            last_notified,
+            ui_dirty,
+            param_defaults,
+
+            input_scratch: [[0.0; crate::MAX_BLOCKSIZE]; 2],
+            output_scratch: [[0.0; crate::MAX_BLOCKSIZE]; 2],
 
             ui_handle: None
         }
@@ -59,75 +123,266 @@ impl<P: Plugin> WrappedPlugin<P> {
     // lifecycle
     ////
 
-    #[inline]
-    pub(crate) fn set_sample_rate(&mut self, sample_rate: f32) {
+    // false if the plugin can't run at `sample_rate`, see `Plugin::supports_sample_rate()`. it is
+    // then torn down, and outputs silence until it's given a rate it does support.
+    pub(crate) fn set_sample_rate(&mut self, sample_rate: f32) -> bool {
+        if !P::supports_sample_rate(sample_rate) {
+            self.plug = None;
+            return false;
+        }
+
         self.sample_rate = sample_rate;
         self.smoothed_model.set_sample_rate(sample_rate);
 
-        self.reset();
+        self.build_plugin();
+        self.ui_host_info();
+        true
     }
 
+    // the most frames the host will hand us in a single `process()`, as far as it has told us.
+    pub(crate) fn set_max_block_size(&mut self, max_block_size: usize) {
+        self.max_block_size = Some(max_block_size);
+        self.ui_host_info();
+    }
+
+    #[inline]
+    pub(crate) fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    // nothing to reset before the host has told us the sample rate, the plugin doesn't exist yet.
     #[inline]
     pub(crate) fn reset(&mut self) {
+        if self.plug.is_some() {
+            self.build_plugin();
+        }
+    }
+
+    fn build_plugin(&mut self) {
         let model = self.smoothed_model.as_model();
-        self.plug = P::new(self.sample_rate, &model);
+        self.plug = Some(P::new(self.sample_rate, &model));
         self.smoothed_model.reset(&model);
     }
 
+    #[inline]
+    pub(crate) fn start_processing(&mut self) {
+        if let Some(plug) = self.plug.as_mut() {
+            plug.start_processing();
+        }
+    }
+
+    #[inline]
+    pub(crate) fn stop_processing(&mut self) {
+        if let Some(plug) = self.plug.as_mut() {
+            plug.stop_processing();
+        }
+    }
+
     ////
     // parameters
     ////
 
+    // a value set by the host but not yet applied reads back as set.
     #[inline]
     pub(crate) fn get_parameter(&self, param: &Param<P, <P::Model as Model<P>>::Smooth>) -> f32 {
-        param.get(&self.smoothed_model)
+        self.pending_sets[param.index]
+            .unwrap_or_else(|| param.get(&self.smoothed_model))
     }
 
+    // like `get_parameter()`, formatted for display.
+    pub(crate) fn get_parameter_display(&self, param: &Param<P, <P::Model as Model<P>>::Smooth>,
+        w: &mut dyn io::Write) -> io::Result<()>
+    {
+        match self.pending_sets[param.index] {
+            Some(val) => param.get_display_for(val, w),
+            None => param.get_display(&self.smoothed_model, w)
+        }
+    }
+
+    // host-side parameter changes take effect at the start of the next `process()`, never part of
+    // the way through a block, whichever thread they come in on. they're kept apart from the event
+    // queue so that they can't be mistaken for automation at frame 0, and setting a parameter
+    // again before then replaces the pending value.
     #[inline]
     pub(crate) fn set_parameter(&mut self, param: &'static Param<P, <P::Model as Model<P>>::Smooth>, val: f32) {
-        if param.dsp_notify.is_some() {
-            self.enqueue_event(Event {
-                frame: 0,
-                data: event::Data::Parameter {
-                    param,
-                    val
-                }
-            });
-        } else {
-            param.set(&mut self.smoothed_model, val);
+        self.pending_sets[param.index] = Some(val);
+    }
+
+    fn has_pending_sets(&self) -> bool {
+        self.pending_sets.iter().any(Option::is_some)
+    }
+
+    // ahead of any automation for the first frame, which is in the block and so comes later.
+    fn apply_pending_sets(&mut self) {
+        for &param in <P::Model as Model<P>>::Smooth::PARAMS {
+            if let Some(val) = self.pending_sets[param.index].take() {
+                self.apply_parameter(param, val, 0);
+            }
         }
+    }
+
+    // back to its default, as if the user had set it, e.g. on a double-click in the editor. goes
+    // through `set_parameter()`, and the host is told so that it can record the change.
+    pub(crate) fn reset_parameter(&mut self, idx: usize, host: &dyn HostContext) {
+        let param = match <P::Model as Model<P>>::Smooth::PARAMS.get(idx) {
+            Some(param) => *param,
+            None => return
+        };
+
+        let default = self.param_defaults[idx];
+
+        self.set_parameter(param, default);
+        host.automate(idx, default);
+    }
+
+    #[inline]
+    fn mark_ui_dirty(&mut self, param: &Param<P, <P::Model as Model<P>>::Smooth>) {
+        self.ui_dirty[param.index] = true;
+    }
 
-        self.ui_param_notify(param, val);
+    pub(crate) fn flush_ui_notifications(&mut self) {
+        let params = <P::Model as Model<P>>::Smooth::PARAMS;
+
+        for (idx, &param) in params.iter().enumerate() {
+            if !self.ui_dirty[idx] {
+                continue;
+            }
+
+            self.ui_dirty[idx] = false;
+            self.ui_param_notify(param, param.get(&self.smoothed_model));
+        }
     }
 
-    fn set_parameter_from_event(&mut self, param: &Param<P, <P::Model as Model<P>>::Smooth>, val: f32) {
+    // every parameter change is applied through here, whether it came from the host, automation,
+    // a momentary release or a state load, so that the UI, `dsp_notify`, `Plugin::on_param_sample()`
+    // and `Plugin::on_parameter_changed()` all see the same sequence of changes.
+    fn apply_parameter(&mut self, param: &Param<P, <P::Model as Model<P>>::Smooth>,
+        val: f32, frame: usize)
+    {
         param.set(&mut self.smoothed_model, val);
+        self.mark_ui_dirty(param);
 
-        if let Some(dsp_notify) = param.dsp_notify {
-            dsp_notify(&mut self.plug);
+        let notify = param.dsp_notify
+            .filter(|_| self.passes_notify_threshold(param, val));
+
+        if let Some(plug) = self.plug.as_mut() {
+            if let Some(dsp_notify) = notify {
+                dsp_notify(plug);
+            }
+
+            plug.on_param_sample(param.index, val, frame);
+            plug.on_parameter_changed(param.index, val, frame);
         }
     }
 
+    fn passes_notify_threshold(&mut self, param: &Param<P, <P::Model as Model<P>>::Smooth>,
+        val: f32) -> bool
+    {
+        let threshold = match param.notify_threshold {
+            Some(t) => t,
+            None => return true
+        };
+
+        let last = &mut self.last_notified[param.index];
+
+        if (val - *last).abs() < threshold {
+            return false;
+        }
+
+        *last = val;
+        true
+    }
+
     ////
     // state
     ////
 
-    pub(crate) fn serialise(&self) -> Option<Vec<u8>>
+    // the model as it will be once the pending host sets are applied, without applying them.
+    fn model_with_pending_sets(&self) -> P::Model {
+        let model = self.smoothed_model.as_model();
+
+        if !self.has_pending_sets() {
+            return model;
+        }
+
+        let mut smoothed = <P::Model as Model<P>>::Smooth::from_model(model);
+
+        for &param in <P::Model as Model<P>>::Smooth::PARAMS {
+            if let Some(val) = self.pending_sets[param.index] {
+                param.set(&mut smoothed, val);
+            }
+        }
+
+        smoothed.as_model()
+    }
+
+    pub(crate) fn serialise(&self) -> serde_json::Result<Vec<u8>>
     {
-        let ser = self.smoothed_model.as_model();
+        let ser = self.model_with_pending_sets();
+
+        if P::SPARSE_STATE {
+            crate::preset::serialise_sparse(&ser)
+        } else {
+            serde_json::to_vec(&ser)
+        }
+    }
+
+    // a last resort for when `serialise()` fails, so that the host still stores something that
+    // loads back into a working plugin.
+    pub(crate) fn serialise_default() -> serde_json::Result<Vec<u8>> {
+        serde_json::to_vec(&P::Model::default())
+    }
 
-        serde_json::to_string(&ser)
-            .map(|s| s.into_bytes())
-            .ok()
+    // a snapshot of the current state for debugging and tooling. not realtime-safe, call it from
+    // the UI thread.
+    pub(crate) fn dump_state(&self) -> serde_json::Value {
+        let model = serde_json::to_value(self.smoothed_model.as_model())
+            .unwrap_or(serde_json::Value::Null);
+
+        let params: Vec<_> = <P::Model as Model<P>>::Smooth::PARAMS.iter()
+            .map(|param| serde_json::json!({
+                "name": param.name,
+                "value": param.get(&self.smoothed_model),
+                "status": format!("{:?}", param.status(&self.smoothed_model))
+            }))
+            .collect();
+
+        serde_json::json!({
+            "sample_rate": self.sample_rate,
+            "model": model,
+            "params": params
+        })
     }
 
     pub(crate) fn deserialise<'de>(&mut self, data: &'de [u8]) {
-        let m: P::Model = match serde_json::from_slice(data) {
+        let m = if P::SPARSE_STATE {
+            crate::preset::deserialise_sparse(data)
+        } else {
+            serde_json::from_slice(data)
+        };
+
+        let mut m: P::Model = match m {
             Ok(m) => m,
             Err(_) => return
         };
 
+        P::validate_model(&mut m, self.sample_rate);
+
+        // the loaded state replaces any parameter changes that are still waiting for a block.
+        self.pending_sets.fill(None);
+        self.events.retain(|ev| !matches!(ev.data, event::Data::Parameter { .. }));
+
         self.smoothed_model.set(&m);
+
+        // the parameters are applied once more from the next block, so that they get reported
+        // like any other change. an open editor is told right away though, in case audio isn't
+        // running.
+        for &param in <P::Model as Model<P>>::Smooth::PARAMS {
+            self.pending_sets[param.index] = Some(param.get(&self.smoothed_model));
+            self.ui_dirty[param.index] = true;
+        }
+
+        self.flush_ui_notifications();
     }
 
     ////
@@ -157,6 +412,21 @@ impl<P: Plugin> WrappedPlugin<P> {
         Self::enqueue_event_in(ev, &mut self.events);
     }
 
+    // hands the events that the plugin emitted during the last `process()` to the host API
+    // backend, in frame order, for translation into the API's own representation. any events left
+    // unconsumed when the iterator is dropped are discarded.
+    #[inline]
+    pub(crate) fn drain_output_events(&mut self) -> impl Iterator<Item = Event<P>> + '_ {
+        self.output_events.drain(..)
+    }
+
+    // after a panic part of the way through a block. what was queued for it would otherwise turn
+    // up in the next one, at frames that no longer mean anything.
+    pub(crate) fn discard_events(&mut self) {
+        self.events.clear();
+        self.output_events.clear();
+    }
+
     ////
     // process
     ////
@@ -169,20 +439,72 @@ impl<P: Plugin> WrappedPlugin<P> {
 
         match ev.data {
             Data::Midi(m) => self.dispatch_midi_event(m),
+            Data::Clock => self.dispatch_midi_clock(),
+            Data::SongPosition(pos) => self.dispatch_song_position(pos),
             Data::Parameter { param, val } => {
-                self.set_parameter_from_event(param, val);
+                let frame = ev.frame;
+                self.apply_parameter(param, val, frame);
             }
         }
     }
 
     #[inline]
     pub(crate) fn process(&mut self, mut musical_time: MusicalTime,
-        input: [&[f32]; 2], mut output: [&mut [f32]; 2],
-        mut nframes: usize)
+        input: [Option<InputChannel>; 2], mut output: [Option<&mut [f32]>; 2],
+        mut nframes: usize, host: &dyn HostContext)
     {
+        // events from the last block that nobody drained are stale now, and their frames would
+        // mix with this block's.
+        self.output_events.clear();
+
+        if self.plug.is_none() {
+            for buf in output.iter_mut().flatten() {
+                let nframes = nframes.min(buf.len());
+                buf[..nframes].fill(0.0);
+            }
+
+            // hang on to parameter changes though. the plugin gets built from the model once the
+            // sample rate is known, so there's no need to notify it.
+            self.apply_pending_sets();
+
+            for ev_idx in 0..self.events.len() {
+                let ev = &self.events[ev_idx];
+
+                if let event::Data::Parameter { param, val } = ev.data {
+                    let frame = ev.frame;
+                    self.apply_parameter(param, val, frame);
+                }
+            }
+
+            self.events.clear();
+            return;
+        }
+
+        // events are queued before we know how long the block is. anything the host scheduled
+        // past its end gets played on the last frame instead of being skipped by the block
+        // splitting below. this keeps the queue sorted, since it only lowers the largest frames.
+        if let Some(last_frame) = nframes.checked_sub(1) {
+            for ev in self.events.iter_mut() {
+                ev.frame = ev.frame.min(last_frame);
+            }
+        }
+
+        let total_frames = nframes;
+
         let mut start = 0;
         let mut ev_idx = 0;
 
+        // hosts don't always provide every channel we declared. the plugin still gets two buffers
+        // either way, silence in place of a missing input and a throwaway for a missing output.
+        let connected_inputs = input.iter().flatten().count() as isize;
+        let connected_outputs = output.iter().flatten().count() as isize;
+
+        if let Some(plug) = self.plug.as_mut() {
+            plug.pre_process(nframes, &musical_time);
+        }
+
+        self.apply_pending_sets();
+
         while nframes > 0 {
             let mut block_frames = nframes;
 
@@ -198,24 +520,41 @@ impl<P: Plugin> WrappedPlugin<P> {
             block_frames = block_frames.min(crate::MAX_BLOCKSIZE);
             let end = start + block_frames;
 
+            for (i, scratch) in self.input_scratch.iter_mut().enumerate() {
+                if let Some(InputChannel::Output(o)) = input[i] {
+                    match &output[o] {
+                        Some(output) =>
+                            scratch[..block_frames].copy_from_slice(&output[start..end]),
+                        None => scratch[..block_frames].fill(0.0)
+                    }
+                }
+            }
+
+            let input_scratch = &self.input_scratch;
+            let channel = |i: usize| match input[i] {
+                Some(InputChannel::Buffer(input)) => &input[start..end],
+                Some(InputChannel::Output(_)) => &input_scratch[i][..block_frames],
+                None => &SILENCE[..block_frames]
+            };
+
+            let input = [channel(0), channel(1)];
+
             let in_bus = AudioBus {
-                connected_channels: 2,
-                buffers: &[
-                    &input[0][start..end],
-                    &input[1][start..end]
-                ]
+                connected_channels: connected_inputs,
+                buffers: &input
             };
 
             let out_bus = AudioBusMut {
-                connected_channels: 2,
+                connected_channels: connected_outputs,
                 buffers: {
                     let split = output.split_at_mut(1);
+                    let scratch = self.output_scratch.split_at_mut(1);
 
                     // "cannot borrow output as mutable more than once"
                     // fuck you borrowck
                     &mut [
-                        &mut split.0[0][start..end],
-                        &mut split.1[0][start..end]
+                        output_or_scratch(&mut split.0[0], &mut scratch.0[0], start, end),
+                        output_or_scratch(&mut split.1[0], &mut scratch.1[0], start, end)
                     ]
                 }
             };
@@ -238,11 +577,21 @@ impl<P: Plugin> WrappedPlugin<P> {
                         Self::enqueue_event_in(ev, output_events);
                     },
 
-                    musical_time: &musical_time
+                    musical_time: &musical_time,
+                    host,
+
+                    instance_seed: self.instance_seed
                 };
 
+                // the smoothers only have MAX_BLOCKSIZE frames of output. anything past that would
+                // be stale data from an earlier block.
+                debug_assert!(block_frames <= crate::MAX_BLOCKSIZE);
+
                 let proc_model = self.smoothed_model.process(block_frames);
-                self.plug.process(&proc_model, &mut context);
+
+                if let Some(plug) = self.plug.as_mut() {
+                    plug.process(&proc_model, &mut context);
+                }
             }
 
             nframes -= block_frames;
@@ -251,8 +600,119 @@ impl<P: Plugin> WrappedPlugin<P> {
             musical_time.step_by_samples(self.sample_rate.into(), block_frames);
         }
 
+        if let Some(plug) = self.plug.as_mut() {
+            if let Some(meter) = plug.clip_meter() {
+                let mut channels: [&[f32]; 2] = [&[], &[]];
+                let mut connected = 0;
+
+                for buf in output.iter().flatten().take(P::OUTPUT_CHANNELS) {
+                    channels[connected] = &buf[..total_frames];
+                    connected += 1;
+                }
+
+                meter.process(&channels[..connected]);
+            }
+
+            plug.post_process();
+        }
         self.events.clear();
+
+        self.release_momentary_parameters(host);
+        self.flush_ui_notifications();
     }
+
+    // whether every parameter's smoother or declicker has come to rest.
+    pub(crate) fn is_settled(&self) -> bool {
+        <P::Model as Model<P>>::Smooth::PARAMS.iter()
+            .all(|param| param.status(&self.smoothed_model) == SmoothStatus::Inactive)
+    }
+
+    // processes silence, discarding the output, until `is_settled()` or until `max_frames` have
+    // gone by. returns how many frames were processed. for offline rendering, e.g. to have all
+    // ramps finished before measuring a steady state.
+    pub(crate) fn settle(&mut self, mut musical_time: MusicalTime, max_frames: usize,
+        host: &dyn HostContext) -> usize
+    {
+        if self.plug.is_none() {
+            return 0;
+        }
+
+        let mut processed = 0;
+
+        // pending host sets only start ramps once they're applied, so always run at least one
+        // block if there are any.
+        let busy = |wrapped: &Self| !wrapped.events.is_empty() || wrapped.has_pending_sets()
+            || !wrapped.is_settled();
+
+        while processed < max_frames && busy(self) {
+            let nframes = (max_frames - processed).min(crate::MAX_BLOCKSIZE);
+            self.process(musical_time.clone(), [None, None], [None, None], nframes, host);
+
+            musical_time.step_by_samples(self.sample_rate.into(), nframes);
+            processed += nframes;
+        }
+
+        processed
+    }
+
+    // released from the top of the next block, like a host set. a set that the host slipped in
+    // meanwhile wins.
+    fn release_momentary_parameters(&mut self, host: &dyn HostContext) {
+        let params = <P::Model as Model<P>>::Smooth::PARAMS;
+
+        for (idx, &param) in params.iter().enumerate() {
+            if !param.momentary || self.pending_sets[idx].is_some() {
+                continue;
+            }
+
+            let default = self.param_defaults[idx];
+
+            if param.get(&self.smoothed_model) == default {
+                continue;
+            }
+
+            self.pending_sets[idx] = Some(default);
+            host.automate(idx, default);
+        }
+    }
+}
+
+// an input channel as the host handed it to us. hosts processing in place give us the same
+// buffer for an input and an output, and since the two can't be borrowed at once, the input is
+// then read back out of the output (index into `output`) before the plugin writes over it.
+#[derive(Clone, Copy)]
+pub(crate) enum InputChannel<'a> {
+    Buffer(&'a [f32]),
+    Output(usize)
+}
+
+static SILENCE: [f32; crate::MAX_BLOCKSIZE] = [0.0; crate::MAX_BLOCKSIZE];
+
+// the `start..end` frames of a host output channel, or of `scratch` if the host didn't provide
+// it. the scratch is cleared first, so that plugins which accumulate into their output don't pick
+// up what was left there.
+#[inline]
+fn output_or_scratch<'a>(output: &'a mut Option<&mut [f32]>,
+    scratch: &'a mut [f32; crate::MAX_BLOCKSIZE], start: usize, end: usize) -> &'a mut [f32]
+{
+    match output {
+        Some(output) => &mut output[start..end],
+        None => {
+            let scratch = &mut scratch[..end - start];
+            scratch.fill(0.0);
+            scratch
+        }
+    }
+}
+
+// a counter keeps instances apart within a process, and hashing it with the randomly-keyed
+// `RandomState` keeps them apart across processes, e.g. in hosts that sandbox each plugin.
+fn next_instance_seed() -> u64 {
+    static INSTANCES: AtomicU64 = AtomicU64::new(0);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(INSTANCES.fetch_add(1, Ordering::Relaxed));
+    hasher.finish()
 }
 
 /////
@@ -264,6 +724,11 @@ pub(crate) trait WrappedPluginMidiInput {
 
     fn midi_input(&mut self, frame: usize, data: [u8; 3]);
     fn dispatch_midi_event(&mut self, data: [u8; 3]);
+    fn dispatch_midi_clock(&mut self);
+    fn dispatch_song_position(&mut self, position: u16);
+
+    fn all_notes_off(&mut self);
+    fn midi_key_name(&self, note: u8) -> Option<&str>;
 }
 
 impl<T: Plugin> WrappedPluginMidiInput for WrappedPlugin<T> {
@@ -278,6 +743,19 @@ impl<T: Plugin> WrappedPluginMidiInput for WrappedPlugin<T> {
     default fn dispatch_midi_event(&mut self, _data: [u8; 3]) {
         return
     }
+
+    default fn dispatch_midi_clock(&mut self) {
+    }
+
+    default fn dispatch_song_position(&mut self, _position: u16) {
+    }
+
+    default fn all_notes_off(&mut self) {
+    }
+
+    default fn midi_key_name(&self, _note: u8) -> Option<&str> {
+        None
+    }
 }
 
 impl<T: MidiReceiver> WrappedPluginMidiInput for WrappedPlugin<T> {
@@ -286,15 +764,61 @@ impl<T: MidiReceiver> WrappedPluginMidiInput for WrappedPlugin<T> {
     }
 
     fn midi_input(&mut self, frame: usize, data: [u8; 3]) {
+        let data = match data[0] {
+            0xF8 if T::DECODE_MIDI_SYNC => event::Data::Clock,
+
+            0xF2 if T::DECODE_MIDI_SYNC => {
+                let pos = ((data[2] as u16 & 0x7F) << 7) | (data[1] as u16 & 0x7F);
+                event::Data::SongPosition(pos)
+            },
+
+            _ => event::Data::Midi(data)
+        };
+
         self.enqueue_event(Event {
             frame,
-            data: event::Data::Midi(data)
+            data
         })
     }
 
     fn dispatch_midi_event(&mut self, data: [u8; 3]) {
+        let plug = match self.plug.as_mut() {
+            Some(plug) => plug,
+            None => return
+        };
+
         let model = self.smoothed_model.current_value();
-        self.plug.midi_input(&model, data)
+        plug.midi_input(&model, data);
+
+        // control change, "all sound off" or "all notes off"
+        if crate::util::midi_status(&data) == 0xB0 && (data[1] == 120 || data[1] == 123) {
+            plug.all_notes_off();
+        }
+    }
+
+    fn dispatch_midi_clock(&mut self) {
+        if let Some(plug) = self.plug.as_mut() {
+            let model = self.smoothed_model.current_value();
+            plug.midi_clock(&model)
+        }
+    }
+
+    fn dispatch_song_position(&mut self, position: u16) {
+        if let Some(plug) = self.plug.as_mut() {
+            let model = self.smoothed_model.current_value();
+            plug.song_position(&model, position)
+        }
+    }
+
+    fn all_notes_off(&mut self) {
+        if let Some(plug) = self.plug.as_mut() {
+            plug.all_notes_off()
+        }
+    }
+
+    fn midi_key_name(&self, note: u8) -> Option<&str> {
+        self.plug.as_ref()
+            .and_then(|plug| plug.midi_key_name(note))
     }
 }
 
@@ -307,6 +831,9 @@ pub(crate) trait WrappedPluginUI<P: Plugin> {
 
     fn ui_param_notify(&self,
         param: &'static Param<P, <P::Model as Model<P>>::Smooth>, val: f32);
+
+    fn poll_ui_messages(&mut self, host: &dyn HostContext);
+    fn ui_host_info(&self);
 }
 
 impl<P: Plugin> WrappedPluginUI<P> for WrappedPlugin<P> {
@@ -317,6 +844,14 @@ impl<P: Plugin> WrappedPluginUI<P> for WrappedPlugin<P> {
         _param: &'static Param<P, <P::Model as Model<P>>::Smooth>, _val: f32)
     {
     }
+
+    #[inline]
+    default fn poll_ui_messages(&mut self, _host: &dyn HostContext) {
+    }
+
+    #[inline]
+    default fn ui_host_info(&self) {
+    }
 }
 
 impl<P: PluginUI> WrappedPluginUI<P> for WrappedPlugin<P> {
@@ -330,4 +865,20 @@ impl<P: PluginUI> WrappedPluginUI<P> for WrappedPlugin<P> {
             P::ui_param_notify(ui_handle, param, val);
         }
     }
+
+    #[inline]
+    fn poll_ui_messages(&mut self, host: &dyn HostContext) {
+        self.flush_ui_notifications();
+
+        if let Some(ui_handle) = self.ui_handle.as_ref() {
+            P::ui_idle(ui_handle, host);
+        }
+    }
+
+    #[inline]
+    fn ui_host_info(&self) {
+        if let Some(ui_handle) = self.ui_handle.as_ref() {
+            P::ui_host_info(ui_handle, self.sample_rate, self.max_block_size);
+        }
+    }
 }
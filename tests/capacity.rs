@@ -0,0 +1,126 @@
+// sizing an `Instance`'s event queues up front, so that processing never allocates.
+
+#[macro_use]
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use serde::{Serialize, Deserialize};
+
+use baseplug::{
+    MidiReceiver,
+    ProcessContext,
+    Instance,
+    Event,
+    event::Data
+};
+
+
+////
+// allocation counting
+////
+
+// counts the allocations made on each thread, so that tests running in parallel don't see each
+// other's.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|count| count.get())
+}
+
+////
+// plugin
+////
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct EchoModel {
+        #[model(min = 0.0, max = 127.0)]
+        #[parameter(name = "velocity")]
+        #[unsmoothed]
+        velocity: f32
+    }
+}
+
+// takes MIDI and emits a note on every frame, at its velocity parameter.
+#[derive(Default)]
+struct Echo;
+
+fixture! {
+    Echo("echo", EchoModel, 2 => 2) {
+        fn process(&mut self, model: &EchoModelProcess, ctx: &mut ProcessContext<Self>) {
+            for i in 0..ctx.nframes {
+                (ctx.enqueue_event)(Event {
+                    frame: i,
+                    data: Data::Midi([0x90, 60, *model.velocity as u8])
+                });
+            }
+        }
+    }
+}
+
+impl MidiReceiver for Echo {
+    fn midi_input(&mut self, _model: &EchoModelProcess, _data: [u8; 3]) {
+    }
+}
+
+// queues an event on every frame of a block, processes it and returns how many allocations that
+// took.
+fn busy_block(instance: &mut Instance<Echo>, nframes: usize) -> usize {
+    let input = vec![0.0; nframes];
+    let mut left = vec![0.0; nframes];
+    let mut right = vec![0.0; nframes];
+
+    let before = allocations();
+
+    for i in 0..nframes {
+        instance.midi_input(i, [0x90, 60, 100]);
+    }
+
+    instance.process([&input, &input], [&mut left, &mut right], nframes);
+
+    allocations() - before
+}
+
+#[test]
+fn queues_dont_grow_within_their_capacity() {
+    let mut instance = Instance::<Echo>::with_capacity(48000.0, 1024, 1024);
+
+    assert_eq!(busy_block(&mut instance, 1024), 0);
+    assert_eq!(instance.drain_output_events().count(), 1024);
+
+    // and the queues are just as big for the next block.
+    assert_eq!(busy_block(&mut instance, 1024), 0);
+}
+
+#[test]
+fn small_queues_grow() {
+    let mut instance = Instance::<Echo>::with_capacity(48000.0, 16, 16);
+
+    assert!(busy_block(&mut instance, 1024) > 0);
+    assert_eq!(instance.drain_output_events().count(), 1024);
+}
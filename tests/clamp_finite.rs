@@ -0,0 +1,28 @@
+// sanitising values with `clamp_finite()` before they reach DSP that can't take them.
+
+use baseplug::util::clamp_finite;
+
+
+#[test]
+fn parameters_are_kept_finite() {
+    // e.g. a filter cutoff on its way into `tan()`.
+    let (min, max) = (20.0, 20000.0);
+
+    assert_eq!(clamp_finite(f32::NAN, min, max), min);
+    assert_eq!(clamp_finite(f32::INFINITY, min, max), max);
+    assert_eq!(clamp_finite(f32::NEG_INFINITY, min, max), min);
+
+    assert_eq!(clamp_finite(0.0, min, max), min);
+    assert_eq!(clamp_finite(1e9, min, max), max);
+    assert_eq!(clamp_finite(440.0, min, max), 440.0);
+}
+
+#[test]
+fn samples_are_kept_finite() {
+    let input = [f32::NAN, f32::INFINITY, f32::NEG_INFINITY, -4.0, 0.5, -0.25];
+    let output: Vec<_> = input.iter()
+        .map(|&x| clamp_finite(x, -1.0, 1.0))
+        .collect();
+
+    assert_eq!(output, vec![-1.0, 1.0, -1.0, -1.0, 0.5, -0.25]);
+}
@@ -0,0 +1,115 @@
+// clip detection with `ClipMeter`, on its own and hooked into the wrapper.
+
+#[macro_use]
+mod common;
+
+use std::sync::Mutex;
+
+use serde::{Serialize, Deserialize};
+
+use baseplug::{
+    ProcessContext,
+    Instance,
+    ClipMeter,
+    ClipIndicator
+};
+
+
+baseplug::model! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct GainModel {
+        #[model(min = 0.0, max = 2.0)]
+        #[parameter(name = "gain")]
+        #[unsmoothed]
+        gain: f32
+    }
+}
+
+impl Default for GainModel {
+    fn default() -> Self {
+        Self {
+            gain: 1.0
+        }
+    }
+}
+
+struct MeteredGain {
+    meter: ClipMeter
+}
+
+// where the test picks up the indicator of the plugin's meter.
+static INDICATOR: Mutex<Option<ClipIndicator>> = Mutex::new(None);
+
+fixture! {
+    MeteredGain("metered gain", GainModel, 2 => 2) {
+        fn new(sample_rate: f32, _model: &GainModel) -> Self {
+            let (meter, indicator) = ClipMeter::new(sample_rate, 10.0);
+            *INDICATOR.lock().unwrap() = Some(indicator);
+
+            Self {
+                meter
+            }
+        }
+
+        fn process(&mut self, model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let input = &ctx.inputs[0].buffers;
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = input[0][i] * *model.gain;
+                output[1][i] = input[1][i] * *model.gain;
+            }
+        }
+
+        fn clip_meter(&mut self) -> Option<&mut ClipMeter> {
+            Some(&mut self.meter)
+        }
+    }
+}
+
+#[test]
+fn clip_lights_up_and_decays() {
+    // a hold of 100 frames.
+    let (mut meter, indicator) = ClipMeter::new(10000.0, 10.0);
+
+    meter.process(&[[0.5; 64], [-1.0; 64]]);
+    assert!(!indicator.is_clipping());
+
+    let mut clipping = [0.0; 64];
+    clipping[40] = -1.5;
+
+    meter.process(&[[0.0; 64], clipping]);
+    assert!(indicator.is_clipping());
+
+    meter.process(&[[0.0; 64], [0.0; 64]]);
+    assert!(indicator.is_clipping());
+
+    meter.process(&[[0.0; 64], [0.0; 64]]);
+    assert!(!indicator.is_clipping());
+
+    meter.process(&[[f32::NAN; 1]]);
+    assert!(indicator.is_clipping());
+}
+
+#[test]
+fn wrapper_feeds_the_meter() {
+    let mut instance = Instance::<MeteredGain>::new(48000.0);
+    let indicator = INDICATOR.lock().unwrap().clone().unwrap();
+
+    let quiet = vec![0.75; 128];
+
+    instance.process_owned(&[quiet.clone(), quiet.clone()], 128);
+    assert!(!indicator.is_clipping());
+
+    instance.set_parameter(0, 1.0);
+    instance.process_owned(&[quiet.clone(), quiet.clone()], 128);
+    assert!(indicator.is_clipping());
+
+    // 10ms is 480 frames at 48kHz.
+    instance.set_parameter(0, 0.5);
+    for _ in 0..4 {
+        instance.process_owned(&[quiet.clone(), quiet.clone()], 128);
+    }
+
+    assert!(!indicator.is_clipping());
+}
@@ -0,0 +1,49 @@
+// helpers shared between the integration tests.
+
+// implements `Plugin` for a test fixture, filling in the metadata every fixture shares so that the
+// body only has to say what the fixture does. fixtures are built with `Default` unless the body
+// starts with its own `new()`.
+//
+//     fixture! {
+//         NoteProbe("note probe", GainModel, 0 => 1) {
+//             fn process(&mut self, model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+//                 ...
+//             }
+//         }
+//     }
+macro_rules! fixture {
+    ($plug:ident($name:literal, $model:ident, $inputs:literal => $outputs:literal) {
+        fn new $($body:tt)*
+    }) => {
+        impl baseplug::Plugin for $plug {
+            fixture!(@metadata $name, $model, $inputs, $outputs);
+
+            fn new $($body)*
+        }
+    };
+
+    ($plug:ident($name:literal, $model:ident, $inputs:literal => $outputs:literal) {
+        $($body:tt)*
+    }) => {
+        impl baseplug::Plugin for $plug {
+            fixture!(@metadata $name, $model, $inputs, $outputs);
+
+            fn new(_sample_rate: f32, _model: &$model) -> Self {
+                Self::default()
+            }
+
+            $($body)*
+        }
+    };
+
+    (@metadata $name:literal, $model:ident, $inputs:literal, $outputs:literal) => {
+        const NAME: &'static str = $name;
+        const PRODUCT: &'static str = $name;
+        const VENDOR: &'static str = "baseplug tests";
+
+        const INPUT_CHANNELS: usize = $inputs;
+        const OUTPUT_CHANNELS: usize = $outputs;
+
+        type Model = $model;
+    };
+}
@@ -0,0 +1,92 @@
+// `Declick` on its own, outside of a model.
+
+use baseplug::Declick;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    Sine,
+    Saw,
+    Square,
+    Triangle
+}
+
+#[test]
+fn current_is_the_target_after_settling() {
+    let mut mode = Declick::new(Mode::Sine);
+    mode.set_speed_ms(48000.0, 1.0);
+
+    mode.set(Mode::Saw);
+    mode.process(64);
+
+    {
+        let output = mode.output();
+
+        assert_eq!(output.current(), &Mode::Saw);
+        assert_eq!(output.value(), None);
+
+        let mixed = output.crossfade(63, 0.0, 1.0);
+        assert!(mixed > 0.0 && mixed < 1.0);
+    }
+
+    for _ in 0..100 {
+        mode.update_status();
+        mode.process(64);
+    }
+
+    mode.update_status();
+
+    let output = mode.current_value();
+    assert_eq!(output.current(), &Mode::Saw);
+    assert_eq!(output.value(), Some(&Mode::Saw));
+    assert_eq!(output.crossfade(0, 0.0, 1.0), 1.0);
+}
+
+#[test]
+fn sets_while_fading_are_queued() {
+    let mut mode = Declick::new(Mode::Sine);
+
+    mode.set(Mode::Saw);
+    mode.set(Mode::Square);
+
+    // fading to the first value set, ending up at the last.
+    assert_eq!(mode.output().to, &Mode::Saw);
+    assert_eq!(mode.dest(), &Mode::Square);
+
+    // setting the destination again doesn't queue another fade.
+    mode.set(Mode::Square);
+    assert_eq!(mode.output().to, &Mode::Saw);
+    assert_eq!(mode.dest(), &Mode::Square);
+}
+
+#[test]
+fn full_queue_never_repeats_a_value() {
+    let mut mode = Declick::with_queue_depth(Mode::Sine, 1);
+    mode.set_speed_ms(48000.0, 1.0);
+
+    mode.set(Mode::Saw);
+    mode.set(Mode::Square);
+
+    // the queue is full, so this replaces `Square`, leaving a fade from `Saw` to `Saw`. it is
+    // dropped instead.
+    mode.set(Mode::Saw);
+    assert_eq!(mode.dest(), &Mode::Saw);
+
+    mode.set(Mode::Triangle);
+    assert_eq!(mode.dest(), &Mode::Triangle);
+
+    let mut seen = vec![mode.output().to.clone()];
+
+    for _ in 0..1000 {
+        mode.process(64);
+        mode.update_status();
+
+        let to = mode.output().to.clone();
+
+        if seen.last() != Some(&to) {
+            seen.push(to);
+        }
+    }
+
+    assert_eq!(seen, vec![Mode::Saw, Mode::Triangle]);
+}
@@ -0,0 +1,62 @@
+// `DelayLine` on its own, impulse in and delayed impulse out.
+
+use baseplug::DelayLine;
+
+
+// feeds a single impulse through `line` and returns the first `len` samples out.
+fn impulse_response(line: &mut DelayLine, delay: f32, feedback: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| line.process(if i == 0 { 1.0 } else { 0.0 }, delay, feedback))
+        .collect()
+}
+
+#[test]
+fn whole_sample_delay() {
+    let mut line = DelayLine::new(16);
+    let out = impulse_response(&mut line, 5.0, 0.0, 16);
+
+    let mut expected = vec![0.0; 16];
+    expected[5] = 1.0;
+
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn fractional_delay_is_interpolated() {
+    let mut line = DelayLine::new(16);
+    let out = impulse_response(&mut line, 2.25, 0.0, 8);
+
+    assert_eq!(out, vec![0.0, 0.0, 0.75, 0.25, 0.0, 0.0, 0.0, 0.0]);
+}
+
+#[test]
+fn delay_survives_wrapping_around() {
+    let mut line = DelayLine::new(4);
+
+    // go round the buffer a few times first, so that the impulse lands somewhere in the middle.
+    for _ in 0..13 {
+        line.process(0.0, 4.0, 0.0);
+    }
+
+    let out = impulse_response(&mut line, 4.0, 0.0, 8);
+    assert_eq!(out, vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+
+    // and longer delays than the line holds are cut short to its length.
+    line.reset();
+    let out = impulse_response(&mut line, 100.0, 0.0, 8);
+    assert_eq!(out, vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]);
+}
+
+#[test]
+fn feedback_repeats() {
+    let mut line = DelayLine::new(16);
+    let out = impulse_response(&mut line, 3.0, 0.5, 10);
+
+    assert_eq!(out, vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.25]);
+}
+
+#[test]
+#[should_panic(expected = "at least one sample")]
+fn zero_length_is_refused() {
+    DelayLine::new(0);
+}
@@ -0,0 +1,36 @@
+// keeping feedback paths out of the denormal range.
+
+use baseplug::util::{prevent_denormals, add_antidenormal};
+
+
+#[test]
+fn denormals_are_nudged_into_normal_range() {
+    let denormal = f32::MIN_POSITIVE / 4.0;
+    assert!(denormal.is_subnormal());
+
+    assert!(prevent_denormals(denormal).is_normal());
+    assert!(prevent_denormals(-denormal).is_normal());
+    assert!(prevent_denormals(0.0).is_normal());
+
+    for &x in &[1.0f32, -0.5, 0.001, -1e-6] {
+        assert_eq!(prevent_denormals(x), x);
+    }
+}
+
+#[test]
+fn block_offset_alternates() {
+    let denormal = f32::MIN_POSITIVE / 4.0;
+
+    let mut block = [denormal; 7];
+    add_antidenormal(&mut block);
+    assert!(block.iter().all(|x| x.is_normal()));
+
+    // no DC over an even number of samples.
+    let mut block = [0.0f32; 8];
+    add_antidenormal(&mut block);
+    assert_eq!(block.iter().sum::<f32>(), 0.0);
+
+    let mut block = [0.25f32, -0.75, 0.5];
+    add_antidenormal(&mut block);
+    assert_eq!(block, [0.25, -0.75, 0.5]);
+}
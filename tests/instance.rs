@@ -0,0 +1,745 @@
+// drives plugins directly through `Instance`, without a plugin API in between.
+
+#[macro_use]
+mod common;
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use serde::{Serialize, Deserialize};
+
+use baseplug::{
+    MidiReceiver,
+    NoteHandler,
+    ProcessContext,
+    Instance,
+    Event,
+    event::Data
+};
+
+
+baseplug::model! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct GainModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "gain")]
+        #[unsmoothed]
+        gain: f32
+    }
+}
+
+impl Default for GainModel {
+    fn default() -> Self {
+        Self {
+            gain: 1.0
+        }
+    }
+}
+
+#[derive(Default)]
+struct Gain;
+
+fixture! {
+    Gain("gain", GainModel, 2 => 2) {
+        fn process(&mut self, model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let input = &ctx.inputs[0].buffers;
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = input[0][i] * *model.gain;
+                output[1][i] = input[1][i] * *model.gain;
+            }
+        }
+    }
+}
+
+// writes the note number of each note-on on the frame it arrived at, silence everywhere else.
+#[derive(Default)]
+struct NoteProbe {
+    pending: Option<u8>
+}
+
+fixture! {
+    NoteProbe("note probe", GainModel, 0 => 1) {
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = 0.0;
+            }
+
+            // the block is split at each event, so whatever arrived did so on our first frame.
+            if let Some(note) = self.pending.take() {
+                output[0][0] = note as f32;
+            }
+        }
+    }
+}
+
+impl MidiReceiver for NoteProbe {
+    fn midi_input(&mut self, _model: &GainModelProcess, data: [u8; 3]) {
+        if data[0] & 0xF0 == 0x90 {
+            self.pending = Some(data[1]);
+        }
+    }
+}
+
+// sends a note on every beat while the transport is playing, scheduled from the host's timeline.
+// the gain sets the velocity.
+#[derive(Default)]
+struct Metronome;
+
+fixture! {
+    Metronome("metronome", GainModel, 2 => 2) {
+        fn process(&mut self, model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let time = ctx.musical_time;
+
+            if !time.is_playing {
+                return;
+            }
+
+            let samples_per_beat = time.samples_per_beat(ctx.sample_rate as f64);
+            // anything within half a frame of the playhead is on the first frame. blocks don't line
+            // up with beats exactly.
+            let mut beat = (time.beat - 0.5 / samples_per_beat).ceil();
+
+            loop {
+                let frame = ((beat - time.beat) * samples_per_beat).round() as usize;
+
+                if frame >= ctx.nframes {
+                    break;
+                }
+
+                (ctx.enqueue_event)(Event {
+                    frame,
+                    data: Data::Midi([0x90, 36, (*model.gain * 120.0) as u8])
+                });
+
+                beat += 1.0;
+            }
+        }
+    }
+}
+
+// writes the smoothed gain to its first channel, and whether it is still smoothing to its second.
+#[derive(Default)]
+struct SmoothingProbe;
+
+fixture! {
+    SmoothingProbe("smoothing probe", SmoothGainModel, 0 => 2) {
+        fn process(&mut self, model: &SmoothGainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+            let smoothing = if model.gain.is_smoothing() { 1.0 } else { 0.0 };
+
+            for i in 0..ctx.nframes {
+                output[0][i] = model.gain[i];
+                output[1][i] = smoothing;
+            }
+        }
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct MixModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "gain")]
+        #[unsmoothed]
+        gain: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "pan")]
+        pan: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "mix")]
+        mix: f32
+    }
+}
+
+impl Default for MixModel {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            pan: 0.5,
+            mix: 1.0
+        }
+    }
+}
+
+// writes `any_smoothing()` to its first channel and `any_changed()` to its second, both scaled by
+// the (unsmoothed) gain.
+#[derive(Default)]
+struct AnySmoothingProbe;
+
+fixture! {
+    AnySmoothingProbe("any smoothing probe", MixModel, 0 => 2) {
+        fn process(&mut self, model: &MixModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+            let smoothing = if model.any_smoothing() { *model.gain } else { 0.0 };
+            let changed = if model.any_changed() { *model.gain } else { 0.0 };
+
+            for i in 0..ctx.nframes {
+                output[0][i] = smoothing;
+                output[1][i] = changed;
+            }
+        }
+    }
+}
+
+// emits two MIDI events per sub-block, last frame first.
+#[derive(Default)]
+struct ReverseEmitter;
+
+fixture! {
+    ReverseEmitter("reverse emitter", GainModel, 0 => 1) {
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            for &frame in &[ctx.nframes - 1, 0] {
+                (ctx.enqueue_event)(Event {
+                    frame,
+                    data: Data::Midi([0x90, frame as u8, 100])
+                });
+            }
+        }
+    }
+}
+
+impl MidiReceiver for ReverseEmitter {
+    fn midi_input(&mut self, _model: &GainModelProcess, _data: [u8; 3]) {
+    }
+}
+
+// writes a code for each decoded MIDI message on the frame it arrived at: the note number for a
+// note on, its negation for a note off, and 1000 + the controller number for a CC.
+#[derive(Default)]
+struct NoteDecoder {
+    pending: Option<f32>
+}
+
+fixture! {
+    NoteDecoder("note decoder", GainModel, 0 => 1) {
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = 0.0;
+            }
+
+            if let Some(code) = self.pending.take() {
+                output[0][0] = code;
+            }
+        }
+    }
+}
+
+impl NoteHandler for NoteDecoder {
+    fn note_on(&mut self, _model: &GainModelProcess, note: u8, _velocity: u8) {
+        self.pending = Some(note as f32);
+    }
+
+    fn note_off(&mut self, _model: &GainModelProcess, note: u8, _velocity: u8) {
+        self.pending = Some(-(note as f32));
+    }
+
+    fn control_change(&mut self, _model: &GainModelProcess, controller: u8, _value: u8) {
+        self.pending = Some(1000.0 + controller as f32);
+    }
+}
+
+// keeps track of how often, and at which rate, it was constructed.
+struct ConstructionCounter;
+
+static CONSTRUCTIONS: AtomicUsize = AtomicUsize::new(0);
+static CONSTRUCTION_RATE: AtomicU32 = AtomicU32::new(0);
+
+fixture! {
+    ConstructionCounter("construction counter", GainModel, 0 => 1) {
+        fn new(sample_rate: f32, _model: &GainModel) -> Self {
+            CONSTRUCTIONS.fetch_add(1, Ordering::SeqCst);
+            CONSTRUCTION_RATE.store(sample_rate.to_bits(), Ordering::SeqCst);
+            Self
+        }
+
+        fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+// records the seed it was handed.
+#[derive(Default)]
+struct SeedProbe;
+
+static SEEDS: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+fixture! {
+    SeedProbe("seed probe", GainModel, 0 => 1) {
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            SEEDS.lock().unwrap().push(ctx.instance_seed());
+        }
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct SmoothGainModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "gain")]
+        gain: f32
+    }
+}
+
+impl Default for SmoothGainModel {
+    fn default() -> Self {
+        Self {
+            gain: 1.0
+        }
+    }
+}
+
+// writes the gain's target value, rather than the smoothed one, on every frame.
+#[derive(Default)]
+struct GainTarget;
+
+fixture! {
+    GainTarget("gain target", SmoothGainModel, 0 => 1) {
+        fn process(&mut self, model: &SmoothGainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+            let target = model.gain.dest_value();
+
+            for i in 0..ctx.nframes {
+                output[0][i] = target;
+            }
+        }
+    }
+}
+
+// records every parameter change it's told about.
+#[derive(Default)]
+struct ParamRecorder;
+
+static PARAM_SAMPLES: Mutex<Vec<(usize, f32, usize)>> = Mutex::new(Vec::new());
+
+fixture! {
+    ParamRecorder("param recorder", GainModel, 0 => 1) {
+        fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+
+        fn on_param_sample(&mut self, index: usize, normalised: f32, frame: usize) {
+            PARAM_SAMPLES.lock().unwrap().push((index, normalised, frame));
+        }
+    }
+}
+
+// captures the parameter changes applied to it, for logging.
+#[derive(Default)]
+struct ChangeRecorder;
+
+static PARAM_CHANGES: Mutex<Vec<(usize, f32, usize)>> = Mutex::new(Vec::new());
+
+fixture! {
+    ChangeRecorder("change recorder", GainModel, 0 => 1) {
+        fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+
+        fn on_parameter_changed(&mut self, index: usize, normalised: f32, frame: usize) {
+            PARAM_CHANGES.lock().unwrap().push((index, normalised, frame));
+        }
+    }
+}
+
+// a DC source that only runs at 48kHz.
+struct FixedRate;
+
+static FIXED_RATE_CONSTRUCTIONS: AtomicUsize = AtomicUsize::new(0);
+
+fixture! {
+    FixedRate("fixed rate", GainModel, 0 => 1) {
+        fn new(_sample_rate: f32, _model: &GainModel) -> Self {
+            FIXED_RATE_CONSTRUCTIONS.fetch_add(1, Ordering::SeqCst);
+            Self
+        }
+
+        fn supports_sample_rate(sample_rate: f32) -> bool {
+            sample_rate == 48000.0
+        }
+
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = 1.0;
+            }
+        }
+    }
+}
+
+#[test]
+fn automation_is_sample_accurate() {
+    let mut instance = Instance::<Gain>::new(48000.0);
+
+    let ramp = [(0, 0.25), (16, 0.5), (32, 0.75), (48, 1.0)];
+    instance.automate(0, &ramp);
+
+    let output = instance.process_owned(&[vec![1.0; 64], vec![1.0; 64]], 64);
+
+    for channel in output {
+        for (i, &sample) in channel.iter().enumerate() {
+            let expected = ramp[i / 16].1;
+            assert_eq!(sample, expected, "frame {}", i);
+        }
+    }
+}
+
+#[test]
+fn automation_of_unknown_parameter_is_ignored() {
+    let mut instance = Instance::<Gain>::new(48000.0);
+    instance.automate(1, &[(0, 0.0)]);
+
+    let output = instance.process_owned(&[vec![1.0; 8]], 8);
+    assert_eq!(output[0], vec![1.0; 8]);
+}
+
+#[test]
+fn metronome_follows_the_transport() {
+    let mut instance = Instance::<Metronome>::new(48000.0);
+
+    // stopped, nothing plays and the playhead stays put.
+    instance.set_transport(120.0, false, 0.0);
+    instance.process_owned(&[], 1000);
+    assert_eq!(instance.drain_output_events().count(), 0);
+    assert_eq!(instance.musical_time().beat, 0.0);
+
+    // at 120bpm and 48kHz, a beat is 24000 frames. blocks of 1000 frames put each note on
+    // the first frame of a block, as long as time moves on between them.
+    instance.set_transport(120.0, true, 0.0);
+
+    let mut notes = Vec::new();
+
+    for block in 0..50 {
+        instance.process_owned(&[], 1000);
+        notes.extend(instance.drain_output_events().map(|ev| block * 1000 + ev.frame));
+    }
+
+    assert_eq!(notes, vec![0, 24000, 48000]);
+    assert!((instance.musical_time().beat - 50000.0 / 24000.0).abs() < 1e-9);
+
+    // starting halfway through a beat, the first note is half a beat in.
+    instance.set_transport(120.0, true, 0.5);
+    instance.process_owned(&[], 16000);
+
+    let frames: Vec<_> = instance.drain_output_events()
+        .map(|ev| ev.frame)
+        .collect();
+
+    assert_eq!(frames, vec![12000]);
+}
+
+#[test]
+fn owned_buffers_are_processed() {
+    // at unity gain.
+    let mut instance = Instance::<Gain>::new(48000.0);
+
+    let left: Vec<f32> = (0..32).map(|i| i as f32).collect();
+    let right: Vec<f32> = (0..32).map(|i| -(i as f32)).collect();
+
+    let output = instance.process_owned(&[left.clone(), right.clone()], 32);
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0], left);
+    assert_eq!(output[1], right);
+
+    // inputs longer than `nframes` are cut short, missing ones are silent.
+    let output = instance.process_owned(std::slice::from_ref(&left), 16);
+    assert_eq!(output[0], left[..16]);
+    assert_eq!(output[1], vec![0.0; 16]);
+
+    // and there are only as many outputs as the plugin has.
+    let mut instance = Instance::<NoteProbe>::new(48000.0);
+    assert_eq!(instance.process_owned(&[], 16).len(), 1);
+}
+
+#[test]
+fn reset_parameter_restores_default() {
+    let mut instance = Instance::<Gain>::new(48000.0);
+
+    instance.automate(0, &[(0, 0.25)]);
+    instance.process_owned(&[], 8);
+    assert_eq!(instance.get_parameter(0), Some(0.25));
+
+    instance.reset_parameter(0);
+    assert_eq!(instance.get_parameter(0), Some(1.0));
+
+    let output = instance.process_owned(&[vec![1.0; 8]], 8);
+    assert_eq!(output[0], vec![1.0; 8]);
+}
+
+#[test]
+fn notes_are_sample_accurate() {
+    let mut instance = Instance::<NoteProbe>::new(48000.0);
+
+    instance.midi_input(10, [0x90, 60, 100]);
+    instance.midi_input(70, [0x90, 64, 100]);
+
+    let output = instance.process_owned(&[], 128);
+
+    for (i, &sample) in output[0].iter().enumerate() {
+        let expected = match i {
+            10 => 60.0,
+            70 => 64.0,
+            _ => 0.0
+        };
+
+        assert_eq!(sample, expected, "frame {}", i);
+    }
+}
+
+
+#[test]
+fn output_events_are_in_frame_order() {
+    let mut instance = Instance::<ReverseEmitter>::new(48000.0);
+
+    // split the block into sub-blocks at 0..20, 20..50 and 50..64.
+    instance.midi_input(20, [0x90, 0, 0]);
+    instance.midi_input(50, [0x90, 0, 0]);
+    instance.process_owned(&[], 64);
+
+    let frames: Vec<_> = instance.drain_output_events()
+        .map(|ev| ev.frame)
+        .collect();
+
+    assert_eq!(frames, vec![0, 19, 20, 49, 50, 63]);
+}
+
+#[test]
+fn note_handler_decodes_midi() {
+    let mut instance = Instance::<NoteDecoder>::new(48000.0);
+
+    instance.midi_input(0, [0x90, 60, 100]);
+    instance.midi_input(10, [0x91, 62, 0]);
+    instance.midi_input(20, [0x80, 61, 64]);
+    instance.midi_input(30, [0xB0, 7, 127]);
+
+    // ignored, pitch bend isn't decoded.
+    instance.midi_input(40, [0xE0, 0, 64]);
+
+    let output = instance.process_owned(&[], 64);
+
+    for (i, &sample) in output[0].iter().enumerate() {
+        let expected = match i {
+            0 => 60.0,
+            10 => -62.0,
+            20 => -61.0,
+            30 => 1007.0,
+            _ => 0.0
+        };
+
+        assert_eq!(sample, expected, "frame {}", i);
+    }
+}
+
+#[test]
+fn plugin_is_constructed_once_at_the_real_rate() {
+    let mut instance = Instance::<ConstructionCounter>::new(44100.0);
+    instance.process_owned(&[], 64);
+
+    assert_eq!(CONSTRUCTIONS.load(Ordering::SeqCst), 1);
+    assert_eq!(f32::from_bits(CONSTRUCTION_RATE.load(Ordering::SeqCst)), 44100.0);
+}
+
+#[test]
+fn instances_get_their_own_seeds() {
+    let mut a = Instance::<SeedProbe>::new(48000.0);
+    let mut b = Instance::<SeedProbe>::new(48000.0);
+
+    a.process_owned(&[], 8);
+    a.process_owned(&[], 8);
+    b.process_owned(&[], 8);
+
+    let seeds = SEEDS.lock().unwrap();
+
+    assert_eq!(seeds[0], seeds[1]);
+    assert_ne!(seeds[0], seeds[2]);
+}
+
+#[test]
+fn host_sets_apply_from_the_next_block() {
+    let mut instance = Instance::<Gain>::new(48000.0);
+
+    instance.set_parameter(0, 0.25);
+    instance.set_parameter(0, 0.5);
+    assert_eq!(instance.get_parameter(0), Some(0.5));
+
+    instance.automate(0, &[(32, 0.25)]);
+
+    let output = instance.process_owned(&[vec![1.0; 64]], 64);
+
+    for (i, &sample) in output[0].iter().enumerate() {
+        let expected = if i < 32 { 0.5 } else { 0.25 };
+        assert_eq!(sample, expected, "frame {}", i);
+    }
+}
+
+
+#[test]
+fn automation_at_frame_zero_is_kept_apart_from_host_sets() {
+    let mut instance = Instance::<Gain>::new(48000.0);
+
+    instance.automate(0, &[(0, 0.25), (32, 0.75)]);
+    instance.set_parameter(0, 0.5);
+
+    // the host set comes first, the automation is in the block.
+    let output = instance.process_owned(&[vec![1.0; 64]], 64);
+
+    for (i, &sample) in output[0].iter().enumerate() {
+        let expected = if i < 32 { 0.25 } else { 0.75 };
+        assert_eq!(sample, expected, "frame {}", i);
+    }
+}
+
+#[test]
+fn dest_value_is_the_target() {
+    let mut instance = Instance::<GainTarget>::new(48000.0);
+
+    instance.set_parameter(0, 0.25);
+    let output = instance.process_owned(&[], 16);
+
+    // the smoother has barely started moving, but the target is already there.
+    assert_eq!(output[0], vec![0.25; 16]);
+}
+
+#[test]
+fn smoothing_ends_with_the_ramp() {
+    let mut instance = Instance::<SmoothingProbe>::new(48000.0);
+
+    instance.set_parameter(0, 0.25);
+    let output = instance.process_owned(&[], 64);
+    assert_eq!(output[1], vec![1.0; 64]);
+
+    // plain blocks, no `settle()`. the ramp is long done after a second.
+    for _ in 0..750 {
+        instance.process_owned(&[], 64);
+    }
+
+    let output = instance.process_owned(&[], 64);
+    assert_eq!(output[0], vec![0.25; 64]);
+    assert_eq!(output[1], vec![0.0; 64]);
+}
+
+#[test]
+fn settle_finishes_smoothing() {
+    let mut instance = Instance::<SmoothingProbe>::new(48000.0);
+
+    instance.set_parameter(0, 0.25);
+    assert!(!instance.settle(16));
+    assert!(instance.settle(48000));
+
+    let output = instance.process_owned(&[], 16);
+
+    assert_eq!(output[0], vec![0.25; 16]);
+    assert_eq!(output[1], vec![0.0; 16]);
+}
+
+
+#[test]
+fn param_samples_are_reported() {
+    let mut instance = Instance::<ParamRecorder>::new(48000.0);
+
+    instance.set_parameter(0, 0.5);
+    instance.automate(0, &[(3, 0.25), (40, 0.75)]);
+    instance.process_owned(&[], 64);
+
+    let samples = PARAM_SAMPLES.lock().unwrap();
+    assert_eq!(*samples, vec![(0, 0.5, 0), (0, 0.25, 3), (0, 0.75, 40)]);
+}
+
+#[test]
+fn applied_changes_are_captured() {
+    let mut instance = Instance::<ChangeRecorder>::new(48000.0);
+
+    instance.set_parameter(0, 0.5);
+    instance.automate(0, &[(3, 0.25), (40, 0.75)]);
+    instance.process_owned(&[], 64);
+
+    // repeated host sets between blocks coalesce into one.
+    instance.set_parameter(0, 0.1);
+    instance.set_parameter(0, 0.2);
+    instance.automate(0, &[(8, 0.3)]);
+    instance.process_owned(&[], 64);
+
+    let changes = PARAM_CHANGES.lock().unwrap();
+    assert_eq!(*changes, vec![
+        (0, 0.5, 0), (0, 0.25, 3), (0, 0.75, 40),
+        (0, 0.2, 0), (0, 0.3, 8)
+    ]);
+}
+
+#[test]
+fn any_smoothing_covers_every_field() {
+    let mut instance = Instance::<AnySmoothingProbe>::new(48000.0);
+
+    let output = instance.process_owned(&[], 16);
+    assert_eq!(output, vec![vec![0.0; 16], vec![0.0; 16]]);
+
+    // unsmoothed fields never smooth.
+    instance.set_parameter(0, 0.5);
+    let output = instance.process_owned(&[], 16);
+    assert_eq!(output, vec![vec![0.0; 16], vec![0.0; 16]]);
+
+    // only the last field moves.
+    instance.set_parameter(2, 0.0);
+    let output = instance.process_owned(&[], 16);
+    assert_eq!(output, vec![vec![0.5; 16], vec![0.5; 16]]);
+
+    assert!(instance.settle(48000));
+    let output = instance.process_owned(&[], 16);
+    assert_eq!(output, vec![vec![0.0; 16], vec![0.0; 16]]);
+}
+
+#[test]
+fn unsupported_sample_rate_is_rejected() {
+    let mut instance = Instance::<FixedRate>::new(44100.0);
+    assert_eq!(FIXED_RATE_CONSTRUCTIONS.load(Ordering::SeqCst), 0);
+    assert_eq!(instance.process_owned(&[], 16)[0], vec![0.0; 16]);
+
+    assert!(instance.set_sample_rate(48000.0));
+    assert_eq!(FIXED_RATE_CONSTRUCTIONS.load(Ordering::SeqCst), 1);
+    assert_eq!(instance.process_owned(&[], 16)[0], vec![1.0; 16]);
+
+    // a running plugin is torn down too.
+    assert!(!instance.set_sample_rate(96000.0));
+    assert_eq!(instance.process_owned(&[], 16)[0], vec![0.0; 16]);
+}
+
+
+#[test]
+fn state_dump_shows_current_values() {
+    let mut instance = Instance::<Gain>::new(48000.0);
+
+    instance.set_parameter(0, 0.25);
+    instance.process_owned(&[], 8);
+
+    assert_eq!(instance.dump_state(), serde_json::json!({
+        "sample_rate": 48000.0,
+        "model": { "gain": 0.25 },
+        "params": [
+            { "name": "gain", "value": 0.25, "status": "Inactive" }
+        ]
+    }));
+}
+
+
+#[test]
+fn undrained_events_dont_pile_up() {
+    let mut instance = Instance::<ReverseEmitter>::new(48000.0);
+
+    instance.process_owned(&[], 64);
+    instance.process_owned(&[], 32);
+
+    // only the last block's events, at its own frames.
+    let events: Vec<_> = instance.drain_output_events()
+        .map(|ev| (ev.frame, ev.data))
+        .collect();
+
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], (0, Data::Midi([0x90, 0, 100]))));
+    assert!(matches!(events[1], (31, Data::Midi([0x90, 31, 100]))));
+}
@@ -0,0 +1,29 @@
+// splitting MIDI status bytes into message type and channel.
+
+use baseplug::util::{midi_status, midi_channel};
+
+
+#[test]
+fn status_and_channel_are_split() {
+    // note on, first channel.
+    assert_eq!(midi_status(&[0x90, 60, 100]), 0x90);
+    assert_eq!(midi_channel(&[0x90, 60, 100]), 0);
+
+    // note off, last channel.
+    assert_eq!(midi_status(&[0x8F, 60, 0]), 0x80);
+    assert_eq!(midi_channel(&[0x8F, 60, 0]), 15);
+
+    // control change on channel 10, where drums usually go.
+    assert_eq!(midi_status(&[0xB9, 123, 0]), 0xB0);
+    assert_eq!(midi_channel(&[0xB9, 123, 0]), 9);
+}
+
+#[test]
+fn data_bytes_are_ignored() {
+    for channel in 0..16 {
+        let data = [0xE0 | channel, 0x7F, 0x7F];
+
+        assert_eq!(midi_status(&data), 0xE0);
+        assert_eq!(midi_channel(&data), channel);
+    }
+}
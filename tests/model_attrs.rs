@@ -0,0 +1,75 @@
+//! doc comments and `#[cfg]` on model fields, as they come out of `model!`. a public model in a
+//! crate that denies missing docs only builds if every doc comment made it through.
+
+#![deny(missing_docs)]
+
+#[macro_use]
+mod common;
+
+use serde::{Serialize, Deserialize};
+
+use baseplug::{
+    Model,
+    Parameters,
+    ProcessContext
+};
+
+
+baseplug::model! {
+    /// a documented, public model.
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    pub struct DocumentedModel {
+        /// how loud, smoothed.
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "level")]
+        pub level: f32,
+
+        /// which way round.
+        #[cfg(all())]
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "polarity", switch)]
+        #[unsmoothed]
+        pub polarity: f32,
+
+        /// never built.
+        #[cfg(any())]
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "missing")]
+        pub missing: f32
+    }
+}
+
+#[derive(Default)]
+struct Documented;
+
+fixture! {
+    Documented("documented", DocumentedModel, 2 => 2) {
+        fn process(&mut self, model: &DocumentedModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = model.level[i];
+                output[1][i] = *model.polarity;
+            }
+        }
+    }
+}
+
+#[test]
+fn documented_cfg_fields_survive() {
+    type Smooth = <DocumentedModel as Model<Documented>>::Smooth;
+
+    // the enabled field is there, the disabled one isn't, not even as a parameter.
+    let model = DocumentedModel {
+        level: 0.5,
+        polarity: 1.0
+    };
+
+    assert_eq!(model.polarity, 1.0);
+
+    let names: Vec<_> = <Smooth as Parameters<Documented, _>>::PARAMS.iter()
+        .map(|param| param.name)
+        .collect();
+
+    assert_eq!(names, vec!["level", "polarity"]);
+}
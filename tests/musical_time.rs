@@ -0,0 +1,36 @@
+// placing beats within a block.
+
+use baseplug::MusicalTime;
+
+
+fn at(beat: f64) -> MusicalTime {
+    MusicalTime {
+        bpm: 120.0,
+        beat,
+        is_playing: true
+    }
+}
+
+#[test]
+fn beats_land_on_frames() {
+    // 24000 frames per beat at 120bpm and 48kHz.
+    assert_eq!(at(0.0).next_beat_boundary(1.0, 48000.0), (0.0, 0));
+    assert_eq!(at(0.5).next_beat_boundary(1.0, 48000.0), (1.0, 12000));
+    assert_eq!(at(0.5).next_beat_boundary(0.25, 48000.0), (0.5, 0));
+
+    // between two samples, the later one.
+    assert_eq!(at(0.0).frame_of_beat(1.5 / 24000.0, 48000.0), 2);
+}
+
+#[test]
+fn rounding_errors_dont_push_beats_back() {
+    // a position added up block by block, a hair short of where it should be.
+    let mut beat = 0.0;
+    for _ in 0..24 {
+        beat += 1000.0 / 24000.0;
+    }
+
+    assert!(beat < 1.0);
+    assert_eq!(at(beat).next_beat_boundary(1.0, 48000.0).1, 0);
+    assert_eq!(at(0.5).frame_of_beat(beat + 0.5, 48000.0), 24000);
+}
@@ -0,0 +1,423 @@
+// parameter metadata and display strings, as generated by `model!`.
+
+#[macro_use]
+mod common;
+
+use serde::{Serialize, Deserialize};
+
+use baseplug::{
+    Model,
+    SmoothModel,
+    Parameters,
+    ProcessContext
+};
+
+
+baseplug::model! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct CompressorModel {
+        #[model(min = 1.0, max = 20.0)]
+        #[parameter(name = "ratio", unit_suffix = ":1")]
+        ratio: f32,
+
+        #[model(min = -24.0, max = 24.0)]
+        #[parameter(name = "makeup", unit = "Decibels", stored = "dsp")]
+        makeup: f32,
+
+        #[model(min = 0.0, max = 2000.0)]
+        #[parameter(name = "release", label = "ms", gradient = "Logarithmic")]
+        release: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "sidechain", switch)]
+        #[unsmoothed]
+        sidechain: f32,
+
+        #[model(min = -24.0, max = 24.0)]
+        #[parameter(name = "output", unit = "Decibels", stored = "unit")]
+        output: f32
+    }
+}
+
+impl Default for CompressorModel {
+    fn default() -> Self {
+        Self {
+            ratio: 4.0,
+
+            // 0dB
+            makeup: 1.0,
+
+            release: 100.0,
+            sidechain: 0.0,
+
+            // also 0dB, but stored as decibels.
+            output: 0.0
+        }
+    }
+}
+
+#[derive(Default)]
+struct Compressor;
+
+fixture! {
+    Compressor("compressor", CompressorModel, 2 => 2) {
+        fn process(&mut self, _model: &CompressorModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+type Smooth = <CompressorModel as Model<Compressor>>::Smooth;
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct GatedModel {
+        #[cfg(any())]
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "never")]
+        never: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "first")]
+        first: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "second", index = 0)]
+        second: f32,
+
+        #[cfg(all())]
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "always")]
+        always: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "last")]
+        last: f32
+    }
+}
+
+#[derive(Default)]
+struct Gated;
+
+fixture! {
+    Gated("gated", GatedModel, 2 => 2) {
+        fn process(&mut self, _model: &GatedModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct PinnedModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "first")]
+        first: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "second", index = 0)]
+        second: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "third", index = 1)]
+        third: f32
+    }
+}
+
+#[derive(Default)]
+struct Pinned;
+
+fixture! {
+    Pinned("pinned", PinnedModel, 2 => 2) {
+        fn process(&mut self, _model: &PinnedModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct ReverbModel {
+        #[model(min = 0.0, max = 10.0)]
+        #[parameter(name = "damping", inverted)]
+        #[unsmoothed]
+        damping: f32,
+
+        #[model(min = 0.0, max = 100.0)]
+        #[parameter(name = "size", curve = "squared")]
+        size: f32
+    }
+}
+
+// a plugin-defined curve, finer towards the bottom of the range.
+mod squared {
+    pub fn from_normalised(x: f32) -> f32 {
+        x * x
+    }
+
+    pub fn to_normalised(x: f32) -> f32 {
+        x.sqrt()
+    }
+}
+
+// outputs its damping and size, rather than doing anything that sounds like a reverb.
+#[derive(Default)]
+struct Reverb;
+
+fixture! {
+    Reverb("reverb", ReverbModel, 2 => 2) {
+        fn process(&mut self, model: &ReverbModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = *model.damping;
+                output[1][i] = model.size[i];
+            }
+        }
+    }
+}
+
+type ReverbSmooth = <ReverbModel as Model<Reverb>>::Smooth;
+
+baseplug::model! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct GateModel {
+        #[model(min = -60.0, max = -6.0)]
+        #[parameter(name = "threshold", unit = "Decibels", stored = "dsp")]
+        threshold: f32,
+
+        #[model(min = -60.0, max = -6.0)]
+        #[parameter(name = "floor", unit = "Decibels", stored = "unit")]
+        floor: f32
+    }
+}
+
+impl Default for GateModel {
+    fn default() -> Self {
+        Self {
+            // -20dB
+            threshold: 0.1,
+            floor: -20.0
+        }
+    }
+}
+
+// a range that's below 0dB all the way.
+#[derive(Default)]
+struct Gate;
+
+fixture! {
+    Gate("gate", GateModel, 2 => 2) {
+        fn process(&mut self, _model: &GateModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+#[test]
+fn unit_suffix_is_displayed() {
+    let model = <Smooth as SmoothModel<Compressor, _>>::from_model(CompressorModel::default());
+    let param = <Smooth as Parameters<Compressor, _>>::PARAMS[0];
+
+    let mut display = Vec::new();
+    param.get_display(&model, &mut display).unwrap();
+
+    assert_eq!(display, b"4:1");
+    assert_eq!(param.get_label(), "");
+}
+
+#[test]
+fn storage_is_dsp_or_unit() {
+    let mut model = <Smooth as SmoothModel<Compressor, _>>::from_model(CompressorModel::default());
+    let params = <Smooth as Parameters<Compressor, _>>::PARAMS;
+    let (makeup, output) = (params[1], params[4]);
+
+    assert_eq!(makeup.get(&model), 0.5);
+    assert_eq!(output.get(&model), 0.5);
+
+    makeup.set(&mut model, 0.75);
+    output.set(&mut model, 0.75);
+
+    // +12dB either way, as a gain coefficient or in decibels.
+    let stored = <Smooth as SmoothModel<Compressor, _>>::as_model(&model);
+    assert!((stored.makeup - 10.0f32.powf(12.0 / 20.0)).abs() < 1e-4);
+    assert!((stored.output - 12.0).abs() < 1e-4);
+
+    for param in &[makeup, output] {
+        assert!((param.get(&model) - 0.75).abs() < 1e-5);
+        assert!((param.normalised_to_unit(0.75) - 12.0).abs() < 1e-4);
+
+        let mut display = Vec::new();
+        param.get_display(&model, &mut display).unwrap();
+        assert_eq!(display, b"12.0");
+    }
+}
+
+#[test]
+fn indices_match_positions() {
+    let params = <Smooth as Parameters<Compressor, _>>::PARAMS;
+
+    for (i, param) in params.iter().enumerate() {
+        assert_eq!(param.index, i);
+    }
+
+    // parameters that are cfg'd out don't take up a position.
+    let gated = <<GatedModel as Model<Gated>>::Smooth as Parameters<Gated, _>>::PARAMS;
+    let names: Vec<_> = gated.iter()
+        .map(|param| (param.index, param.name))
+        .collect();
+
+    // enabled cfgs count.
+    assert_eq!(names, vec![(0, "second"), (1, "first"), (2, "always"), (3, "last")]);
+}
+
+#[test]
+fn param_count_matches_params() {
+    assert_eq!(<CompressorModel as Model<Compressor>>::PARAM_COUNT, 5);
+    assert_eq!(<CompressorModel as Model<Compressor>>::PARAM_COUNT,
+        <Smooth as Parameters<Compressor, _>>::PARAMS.len());
+
+    // cfg'd out fields aren't counted.
+    assert_eq!(<GatedModel as Model<Gated>>::PARAM_COUNT, 4);
+}
+
+#[test]
+fn inverted_range_is_flipped() {
+    let mut model = <ReverbSmooth as SmoothModel<Reverb, _>>::from_model(ReverbModel::default());
+    let param = <ReverbSmooth as Parameters<Reverb, _>>::PARAMS[0];
+
+    assert_eq!(param.normalised_to_unit(0.0), 10.0);
+    assert_eq!(param.normalised_to_unit(1.0), 0.0);
+    assert_eq!(param.unit_to_normalised(10.0), 0.0);
+    assert_eq!(param.unit_to_normalised(0.0), 1.0);
+
+    // the model stores the unit value, but hands the host back the value it set.
+    for i in 0..=10 {
+        let x = i as f32 / 10.0;
+
+        param.set(&mut model, x);
+        let damping = <ReverbSmooth as SmoothModel<Reverb, _>>::as_model(&model).damping;
+        assert!((damping - (10.0 - 10.0 * x)).abs() < 1e-5);
+        assert!((param.get(&model) - x).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn custom_curve_round_trip() {
+    let mut model = <ReverbSmooth as SmoothModel<Reverb, _>>::from_model(ReverbModel::default());
+    let param = <ReverbSmooth as Parameters<Reverb, _>>::PARAMS[1];
+
+    assert_eq!(param.normalised_to_unit(0.0), 0.0);
+    assert_eq!(param.normalised_to_unit(0.5), 25.0);
+    assert_eq!(param.normalised_to_unit(1.0), 100.0);
+    assert_eq!(param.unit_to_normalised(25.0), 0.5);
+
+    param.set(&mut model, 0.5);
+    assert_eq!(<ReverbSmooth as SmoothModel<Reverb, _>>::as_model(&model).size, 25.0);
+
+    for i in 0..=100 {
+        let x = i as f32 / 100.0;
+
+        param.set(&mut model, x);
+        let size = <ReverbSmooth as SmoothModel<Reverb, _>>::as_model(&model).size;
+
+        assert!((size - 100.0 * x * x).abs() < 1e-3, "{} maps to {}", x, size);
+        assert!((param.get(&model) - x).abs() < 1e-5, "{} maps to {}", x, size);
+    }
+}
+
+#[test]
+fn pinned_indices_go_where_theyre_told() {
+    let params = <<PinnedModel as Model<Pinned>>::Smooth as Parameters<Pinned, _>>::PARAMS;
+    let names: Vec<_> = params.iter()
+        .map(|param| param.name)
+        .collect();
+
+    // whatever their field order, with unpinned ones filling the gaps.
+    assert_eq!(names, vec!["second", "third", "first"]);
+}
+
+#[test]
+fn pinned_indices_are_checked() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/duplicate_index.rs");
+    t.compile_fail("tests/ui/index_out_of_range.rs");
+}
+
+#[test]
+fn unit_values_are_in_decibels() {
+    let param = <Smooth as Parameters<Compressor, _>>::PARAMS[1];
+
+    assert!(param.normalised_to_unit(0.5).abs() < 1e-4);
+    assert!((param.normalised_to_unit(0.75) - 12.0).abs() < 1e-4);
+    assert!((param.unit_to_normalised(12.0) - 0.75).abs() < 1e-5);
+    assert!((param.unit_to_normalised(-24.0)).abs() < 1e-5);
+}
+
+#[test]
+fn requested_values_are_displayed() {
+    let model = <Smooth as SmoothModel<Compressor, _>>::from_model(CompressorModel::default());
+    let param = <Smooth as Parameters<Compressor, _>>::PARAMS[1];
+
+    let mut display = Vec::new();
+    param.get_display_for(0.75, &mut display).unwrap();
+    assert_eq!(display, b"12.0");
+
+    // the model itself is still at 0dB.
+    display.clear();
+    param.get_display(&model, &mut display).unwrap();
+    assert_eq!(display, b"0.0");
+}
+
+
+#[test]
+fn logarithmic_round_trip() {
+    let param = <Smooth as Parameters<Compressor, _>>::PARAMS[2];
+
+    assert_eq!(param.normalised_to_unit(0.0), 0.0);
+    assert_eq!(param.normalised_to_unit(1.0), 2000.0);
+    assert_eq!(param.unit_to_normalised(0.0), 0.0);
+    assert_eq!(param.unit_to_normalised(2000.0), 1.0);
+
+    for i in 0..=100 {
+        let x = i as f32 / 100.0;
+        let val = param.normalised_to_unit(x);
+
+        assert!(val.is_finite(), "{} maps to {}", x, val);
+        assert!((param.unit_to_normalised(val) - x).abs() < 1e-5, "{} maps to {}", x, val);
+    }
+
+    // slow at the bottom.
+    assert!(param.normalised_to_unit(0.5) < 200.0);
+}
+
+
+#[test]
+fn switches_snap_to_on_or_off() {
+    let mut model = <Smooth as SmoothModel<Compressor, _>>::from_model(CompressorModel::default());
+    let param = <Smooth as Parameters<Compressor, _>>::PARAMS[3];
+
+    for &(normalised, on) in &[(0.0, false), (0.49, false), (0.5, true), (1.0, true)] {
+        param.set(&mut model, normalised);
+
+        assert_eq!(param.normalised_to_unit(normalised), if on { 1.0 } else { 0.0 });
+        assert_eq!(param.get(&model), if on { 1.0 } else { 0.0 });
+    }
+}
+
+
+#[test]
+fn negative_decibel_ranges_round_trip() {
+    let model = <<GateModel as Model<Gate>>::Smooth as SmoothModel<Gate, _>>::from_model(
+        GateModel::default());
+
+    for param in <<GateModel as Model<Gate>>::Smooth as Parameters<Gate, _>>::PARAMS {
+        assert_eq!(param.unit_to_normalised(-60.0), 0.0);
+        assert_eq!(param.unit_to_normalised(-6.0), 1.0);
+        assert!((param.unit_to_normalised(-33.0) - 0.5).abs() < 1e-5);
+
+        // outside the range, either way.
+        assert_eq!(param.unit_to_normalised(-90.0), 0.0);
+        assert_eq!(param.unit_to_normalised(0.0), 1.0);
+
+        let expected = 40.0 / 54.0;
+        assert!((param.get(&model) - expected).abs() < 1e-5, "{} is at {}", param.name,
+            param.get(&model));
+    }
+}
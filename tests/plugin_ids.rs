@@ -0,0 +1,30 @@
+// plugin class ids derived from a plugin's vendor and name.
+
+use baseplug::api::iid_from_name;
+
+
+const IID: [u8; 16] = iid_from_name("baseplug tests", "gain");
+
+#[test]
+fn iid_is_stable() {
+    assert_eq!(IID, iid_from_name("baseplug tests", "gain"));
+
+    // pinned, since a changed id would make hosts treat existing projects' plugins as missing.
+    assert_eq!(IID, [
+        0x48, 0x05, 0xae, 0xb1, 0x1b, 0x30, 0x81, 0xc9,
+        0x80, 0x87, 0xb0, 0x7b, 0xe8, 0x51, 0xe2, 0x7c
+    ]);
+}
+
+#[test]
+fn iid_depends_on_vendor_and_name() {
+    assert_ne!(IID, iid_from_name("baseplug tests", "gain 2"));
+    assert_ne!(IID, iid_from_name("baseplug test", "sgain"));
+    assert_ne!(IID, iid_from_name("", "baseplug testsgain"));
+}
+
+#[test]
+fn iid_is_a_version_8_uuid() {
+    assert_eq!(IID[6] >> 4, 8);
+    assert_eq!(IID[8] >> 6, 0b10);
+}
@@ -0,0 +1,119 @@
+// sparse preset serialisation and preset bank files, independent of any plugin API.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Serialize, Deserialize};
+
+use baseplug::preset::{serialise_sparse, deserialise_sparse, save_bank, load_bank};
+
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Filter {
+    cutoff: f32,
+    resonance: f32
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Synth {
+    gain: f32,
+    voices: u32,
+    filter: Filter
+}
+
+impl Default for Synth {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            voices: 8,
+            filter: Filter {
+                cutoff: 1000.0,
+                resonance: 0.5
+            }
+        }
+    }
+}
+
+#[test]
+fn defaults_serialise_to_empty() {
+    let data = serialise_sparse(&Synth::default()).unwrap();
+    assert_eq!(data, b"{}");
+
+    let synth: Synth = deserialise_sparse(&data).unwrap();
+    assert_eq!(synth, Synth::default());
+}
+
+#[test]
+fn only_changed_fields_are_saved() {
+    let synth = Synth {
+        voices: 4,
+        filter: Filter {
+            cutoff: 250.0,
+            resonance: 0.5
+        },
+        ..Synth::default()
+    };
+
+    let data = serialise_sparse(&synth).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&data).unwrap();
+
+    assert_eq!(value, serde_json::json!({
+        "voices": 4,
+        "filter": { "cutoff": 250.0 }
+    }));
+
+    assert_eq!(deserialise_sparse::<Synth>(&data).unwrap(), synth);
+}
+
+#[test]
+fn full_state_still_loads() {
+    let synth = Synth {
+        gain: 0.5,
+        ..Synth::default()
+    };
+
+    let data = serde_json::to_vec(&synth).unwrap();
+    assert_eq!(deserialise_sparse::<Synth>(&data).unwrap(), synth);
+}
+
+// a path in the temp dir that no other test (or test run) uses at the same time.
+fn bank_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("baseplug-{}-{}.bank", name, std::process::id()))
+}
+
+#[test]
+fn bank_round_trip() {
+    let presets = vec![
+        Synth::default(),
+        Synth {
+            gain: 0.5,
+            voices: 2,
+            ..Synth::default()
+        }
+    ];
+
+    let path = bank_path("round-trip");
+    save_bank(&path, &presets).unwrap();
+    let loaded: Vec<Synth> = load_bank(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, presets);
+}
+
+// a bank small enough to sit in the write buffer until the end, on a device that's always full.
+#[cfg(target_os = "linux")]
+#[test]
+fn failed_writes_are_reported() {
+    assert!(save_bank("/dev/full", &[Synth::default()]).is_err());
+}
+
+#[test]
+fn newer_banks_are_refused() {
+    let path = bank_path("newer");
+    fs::write(&path, r#"{ "version": 2, "presets": [] }"#).unwrap();
+    let loaded = load_bank::<Synth>(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,66 @@
+// handing values from a loader thread to the audio thread with `SampleSwap`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use baseplug::SampleSwap;
+
+
+// counts every allocation and deallocation in this test binary.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// a single test, so that no other test thread allocates while we're counting.
+#[test]
+fn sample_swap() {
+    let (mut swap, loader) = SampleSwap::<Vec<f32>>::new();
+
+    assert!(!swap.update());
+    assert!(swap.get().is_none());
+
+    loader.load(vec![1.0; 1024]);
+    loader.load(vec![2.0; 1024]);
+
+    // picking up a value, and retiring the previous one, touches the allocator not at all.
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+
+    assert!(swap.update());
+    assert_eq!(swap.get().unwrap()[0], 2.0);
+    assert!(!swap.update());
+
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), before);
+
+    // values loaded on another thread come through the same way. the one replaced here is
+    // freed by the loader, not by us.
+    let loader = thread::spawn(move || {
+        loader.load(vec![3.0; 1024]);
+        loader
+    }).join().unwrap();
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+
+    assert!(swap.update());
+    assert_eq!(swap.get().unwrap()[0], 3.0);
+
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), before);
+
+    loader.collect();
+    assert!(ALLOCATIONS.load(Ordering::SeqCst) > before);
+}
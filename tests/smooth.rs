@@ -0,0 +1,74 @@
+// `Smooth` on its own, outside of a model.
+
+use baseplug::Smooth;
+
+
+#[test]
+fn speed_is_a_time_constant() {
+    let sample_rate = 48000.0;
+
+    for &ms in &[0.5f32, 1.0, 2.5] {
+        let mut smooth = Smooth::new(0.0f32);
+        smooth.set_speed_ms(sample_rate, ms);
+        smooth.set(1.0);
+
+        let frames = (ms * sample_rate / 1000.0) as usize;
+        smooth.process(frames);
+
+        let expected = 1.0 - (-1.0f32).exp();
+        let actual = smooth.output().values[frames - 1];
+
+        assert!((actual - expected).abs() < 1e-4,
+            "{}ms: {} after {} frames, expected {}", ms, actual, frames, expected);
+    }
+}
+
+#[test]
+fn peek_is_the_next_first_frame() {
+    let mut smooth = Smooth::new(0.0f32);
+    smooth.set_speed_ms(48000.0, 1.0);
+
+    let check = |smooth: &mut Smooth<f32>| {
+        let peeked = smooth.peek();
+        smooth.process(16);
+        assert_eq!(peeked, smooth.output().values[0]);
+    };
+
+    // at rest.
+    check(&mut smooth);
+
+    // at the start of a ramp, and half-way through one.
+    smooth.set(1.0);
+    check(&mut smooth);
+    check(&mut smooth);
+
+    // straight after a jump.
+    smooth.reset(0.5);
+    check(&mut smooth);
+}
+
+
+#[test]
+fn changed_compares_against_the_previous_block() {
+    let mut smooth = Smooth::new(0.0f32);
+    smooth.set_speed_ms(48000.0, 1.0);
+
+    smooth.process(64);
+    assert!(!smooth.output().changed());
+
+    // a ramp moves from block to block.
+    smooth.set(1.0);
+    smooth.process(64);
+    assert!(smooth.output().changed());
+    smooth.process(64);
+    assert!(smooth.output().changed());
+
+    // a jump without a ramp counts too, but only for the block it happened in.
+    smooth.reset(0.5);
+    smooth.process(64);
+    assert!(smooth.output().changed());
+    assert!(!smooth.output().is_smoothing());
+
+    smooth.process(64);
+    assert!(!smooth.output().changed());
+}
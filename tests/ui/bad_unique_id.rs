@@ -0,0 +1,40 @@
+use serde::{Serialize, Deserialize};
+
+use baseplug::{Plugin, ProcessContext};
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct GainModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "gain")]
+        gain: f32
+    }
+}
+
+struct Gain;
+
+impl Plugin for Gain {
+    const NAME: &'static str = "gain";
+    const PRODUCT: &'static str = "gain";
+    const VENDOR: &'static str = "baseplug tests";
+
+    const INPUT_CHANNELS: usize = 2;
+    const OUTPUT_CHANNELS: usize = 2;
+
+    type Model = GainModel;
+
+    fn new(_sample_rate: f32, _model: &GainModel) -> Self {
+        Self
+    }
+
+    fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+    }
+}
+
+// kept out of the crate root so that the exported `main()` doesn't clash with ours.
+mod entry {
+    baseplug::vst2!(super::Gain, b"toolong");
+}
+
+fn main() {
+}
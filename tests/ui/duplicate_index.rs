@@ -0,0 +1,15 @@
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct PinnedModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "first", index = 1)]
+        first: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "second", index = 1)]
+        second: f32
+    }
+}
+
+fn main() {
+}
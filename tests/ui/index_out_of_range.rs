@@ -0,0 +1,15 @@
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct PinnedModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "first")]
+        first: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "second", index = 2)]
+        second: f32
+    }
+}
+
+fn main() {
+}
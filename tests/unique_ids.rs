@@ -0,0 +1,16 @@
+// VST2 unique ids given to `vst2!` as literals.
+
+use baseplug::api::vst2::unique_id_from_literal;
+
+
+#[test]
+fn literals_are_accepted() {
+    assert_eq!(unique_id_from_literal("b\"tAnE\""), *b"tAnE");
+    assert_eq!(unique_id_from_literal("\"~MM~\""), *b"~MM~");
+}
+
+#[test]
+fn invalid_literals_fail_the_build() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/bad_unique_id.rs");
+}
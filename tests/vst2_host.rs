@@ -0,0 +1,1808 @@
+// drives plugins through the VST2 entry point the way a host would, with a minimal mock host on
+// the other side of the callback.
+
+#[macro_use]
+mod common;
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use raw_window_handle::HasRawWindowHandle;
+
+use serde::{Serialize, Deserialize};
+
+use baseplug::{
+    Plugin,
+    PluginUI,
+    HostContext,
+    MidiReceiver,
+    Model,
+    MusicalTime,
+    Param,
+    ProcessContext,
+    event::Data,
+    WindowOpenResult,
+    api::vst2::plugin_main
+};
+
+use baseplug::api::vst2::vst2_sys::*;
+
+
+////
+// mock host
+////
+
+static TIME_INFO: TimeInfo = TimeInfo {
+    sample_pos: 0.0,
+    sample_rate: 48000.0,
+    nano_seconds: 0.0,
+    ppq_pos: 4.0,
+    tempo: 123.0,
+    bar_start_pos: 0.0,
+    cycle_start_pos: 0.0,
+    cycle_end_pos: 0.0,
+    time_sig_numerator: 4,
+    time_sig_denominator: 4,
+    smpte_offset: 0,
+    smpte_frame_rate: 0,
+    samples_to_next_clock: 0,
+
+    // tempo only. the position is deliberately marked invalid, and the transport is stopped.
+    flags: time_info_flags::TEMPO_VALID
+};
+
+static IO_CHANGED_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+// every MIDI event plugins have sent us, as (delta frames, bytes).
+static SENT_MIDI: Mutex<Vec<(i32, [u8; 3])>> = Mutex::new(Vec::new());
+
+extern "C" fn host_cb(_effect: *mut AEffect, opcode: i32, _index: i32, _value: isize,
+    ptr: *mut c_void, _opt: f32) -> isize
+{
+    match opcode {
+        host_opcodes::VERSION => 2400,
+        host_opcodes::GET_TIME => &TIME_INFO as *const TimeInfo as isize,
+        host_opcodes::GET_SAMPLE_RATE => 48000,
+        host_opcodes::GET_BLOCK_SIZE => 256,
+
+        host_opcodes::GET_VENDOR_STRING => {
+            let vendor = b"mock vendor\0";
+            unsafe { ptr::copy_nonoverlapping(vendor.as_ptr(), ptr as *mut u8, vendor.len()) };
+            1
+        },
+
+        host_opcodes::IO_CHANGED => {
+            IO_CHANGED_CALLS.fetch_add(1, Ordering::SeqCst);
+            1
+        },
+
+        host_opcodes::PROCESS_EVENTS => {
+            let events = unsafe { &*(ptr as *const Events) };
+            let mut sent = SENT_MIDI.lock().unwrap();
+
+            for i in 0..events.num_events as usize {
+                let ev = unsafe { &*(*events.events.as_ptr().add(i) as *const MidiEvent) };
+                sent.push((ev.delta_frames, [ev.midi_data[0], ev.midi_data[1], ev.midi_data[2]]));
+            }
+
+            1
+        },
+
+        _ => 0
+    }
+}
+
+// not exported by vst2-sys.
+const GET_MIDI_KEY_NAME: i32 = 66;
+
+#[repr(C)]
+struct MidiKeyName {
+    this_program_index: i32,
+    this_key_number: i32,
+    key_name: [c_char; 64],
+    reserved: i32,
+    flags: i32
+}
+
+struct MockHost {
+    effect: *mut AEffect
+}
+
+impl MockHost {
+    fn new<P: Plugin>() -> Self {
+        let host = Self {
+            effect: plugin_main::<P>(host_cb, b"test")
+        };
+
+        host.dispatch(effect_opcodes::OPEN, 0, 0, ptr::null_mut(), 0.0);
+        host.dispatch(effect_opcodes::SET_SAMPLE_RATE, 0, 0, ptr::null_mut(), 48000.0);
+        host.dispatch(effect_opcodes::MAINS_CHANGED, 0, 1, ptr::null_mut(), 0.0);
+
+        host
+    }
+
+    fn effect(&self) -> &AEffect {
+        unsafe { &*self.effect }
+    }
+
+    fn dispatch(&self, opcode: i32, index: i32, value: isize, ptr: *mut c_void, opt: f32)
+        -> isize
+    {
+        (self.effect().dispatcher)(self.effect, opcode, index, value, ptr, opt)
+    }
+
+    fn get_string(&self, opcode: i32, index: i32) -> String {
+        let mut buf = [0u8; 64];
+        self.dispatch(opcode, index, 0, buf.as_mut_ptr() as *mut c_void, 0.0);
+
+        unsafe { CStr::from_ptr(buf.as_ptr() as *const c_char) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn set_parameter(&self, index: i32, val: f32) {
+        (self.effect().set_parameter)(self.effect, index, val)
+    }
+
+    fn get_parameter(&self, index: i32) -> f32 {
+        (self.effect().get_parameter)(self.effect, index)
+    }
+
+    fn process(&self, input: [&[f32]; 2], output: [&mut [f32]; 2]) {
+        let nframes = output[0].len();
+
+        let inputs = [input[0].as_ptr(), input[1].as_ptr()];
+        let mut outputs = [output[0].as_mut_ptr(), output[1].as_mut_ptr()];
+
+        (self.effect().process_replacing)(self.effect,
+            inputs.as_ptr(), outputs.as_mut_ptr(), nframes as i32);
+    }
+
+    // like `process()`, but with the host's channel pointers as given, null ones included.
+    fn process_raw(&self, inputs: *const *const f32, outputs: *mut *mut f32, nframes: usize) {
+        (self.effect().process_replacing)(self.effect, inputs, outputs, nframes as i32);
+    }
+
+    fn get_chunk(&self) -> Vec<u8> {
+        let mut data: *mut c_void = ptr::null_mut();
+        let len = self.dispatch(effect_opcodes::GET_CHUNK, 0, 0,
+            &mut data as *mut *mut c_void as *mut c_void, 0.0);
+
+        assert!(len > 0);
+        unsafe { std::slice::from_raw_parts(data as *const u8, len as usize) }.to_vec()
+    }
+
+    fn set_chunk(&self, chunk: &[u8]) {
+        self.dispatch(effect_opcodes::SET_CHUNK, 0, chunk.len() as isize,
+            chunk.as_ptr() as *mut c_void, 0.0);
+    }
+
+    // `None` when the plugin has no name for `note`.
+    fn midi_key_name(&self, note: i32) -> Option<String> {
+        let mut key_name = MidiKeyName {
+            this_program_index: 0,
+            this_key_number: note,
+            key_name: [0; 64],
+            reserved: 0,
+            flags: 0
+        };
+
+        let named = self.dispatch(GET_MIDI_KEY_NAME, 0, 0,
+            &mut key_name as *mut MidiKeyName as *mut c_void, 0.0);
+
+        if named == 0 {
+            return None;
+        }
+
+        let name = unsafe { CStr::from_ptr(key_name.key_name.as_ptr()) };
+        Some(name.to_string_lossy().into_owned())
+    }
+
+    fn send_midi(&self, delta_frames: i32, data: [u8; 3]) {
+        let ev = MidiEvent {
+            event_type: MIDI_TYPE,
+            byte_size: std::mem::size_of::<MidiEvent>() as i32,
+            delta_frames,
+            flags: 0,
+            note_length: 0,
+            note_offset: 0,
+            midi_data: [data[0], data[1], data[2], 0],
+            detune: 0,
+            note_off_velocity: 0,
+            reserved_1: 0,
+            reserved_2: 0
+        };
+
+        let events = Events {
+            num_events: 1,
+            reserved: ptr::null(),
+            events: [&ev as *const MidiEvent as *const Event, ptr::null()]
+        };
+
+        self.dispatch(effect_opcodes::PROCESS_EVENTS, 0, 0,
+            &events as *const Events as *mut c_void, 0.0);
+    }
+}
+
+impl Drop for MockHost {
+    fn drop(&mut self) {
+        self.dispatch(effect_opcodes::MAINS_CHANGED, 0, 0, ptr::null_mut(), 0.0);
+        self.dispatch(effect_opcodes::CLOSE, 0, 0, ptr::null_mut(), 0.0);
+    }
+}
+
+////
+// plugins
+////
+
+baseplug::model! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct GainModel {
+        #[model(min = -90.0, max = 3.0)]
+        #[parameter(name = "gain", unit = "Decibels", gradient = "Power(0.15)")]
+        gain: f32
+    }
+}
+
+impl Default for GainModel {
+    fn default() -> Self {
+        Self {
+            gain: 1.0
+        }
+    }
+}
+
+#[derive(Default)]
+struct Gain;
+
+fixture! {
+    Gain("gain", GainModel, 2 => 2) {
+        fn process(&mut self, model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let input = &ctx.inputs[0].buffers;
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = input[0][i] * model.gain[i];
+                output[1][i] = input[1][i] * model.gain[i];
+            }
+        }
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct EmptyModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "unused")]
+        unused: f32
+    }
+}
+
+// writes the transport as it sees it into its outputs.
+#[derive(Default)]
+struct TransportProbe;
+
+fixture! {
+    TransportProbe("transport probe", EmptyModel, 2 => 2) {
+        fn process(&mut self, _model: &EmptyModelProcess, ctx: &mut ProcessContext<Self>) {
+            let time = ctx.musical_time;
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = time.bpm as f32;
+                output[1][i] = time.beat as f32 + if time.is_playing { 1000.0 } else { 0.0 };
+            }
+        }
+    }
+}
+
+// reports a fixed latency from every block, as if oversampling had just been switched on.
+#[derive(Default)]
+struct LatencyProbe;
+
+fixture! {
+    LatencyProbe("latency probe", EmptyModel, 2 => 2) {
+        fn process(&mut self, _model: &EmptyModelProcess, ctx: &mut ProcessContext<Self>) {
+            ctx.report_latency_changed(64);
+        }
+    }
+}
+
+// writes what it heard on each frame to its first output: the status byte for anything that goes
+// through `midi_input()`, -1 for a clock tick, and 10000 + the position for a song position
+// pointer.
+#[derive(Default)]
+struct SyncDecoder {
+    pending: Option<f32>
+}
+
+fixture! {
+    SyncDecoder("sync decoder", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = 0.0;
+                output[1][i] = 0.0;
+            }
+
+            if let Some(code) = self.pending.take() {
+                output[0][0] = code;
+            }
+        }
+    }
+}
+
+impl MidiReceiver for SyncDecoder {
+    const DECODE_MIDI_SYNC: bool = true;
+
+    fn midi_input(&mut self, _model: &GainModelProcess, data: [u8; 3]) {
+        self.pending = Some(data[0] as f32);
+    }
+
+    fn midi_clock(&mut self, _model: &GainModelProcess) {
+        self.pending = Some(-1.0);
+    }
+
+    fn song_position(&mut self, _model: &GainModelProcess, position: u16) {
+        self.pending = Some(10000.0 + position as f32);
+    }
+}
+
+// asks the host everything it can from `process()`, and keeps the answers.
+#[derive(Default)]
+struct HostQuerier;
+
+type HostAnswers = (Option<f32>, Option<usize>, Option<f64>, Option<String>);
+static HOST_ANSWERS: Mutex<Option<HostAnswers>> = Mutex::new(None);
+
+fixture! {
+    HostQuerier("host querier", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let host = ctx.host;
+
+            *HOST_ANSWERS.lock().unwrap() = Some((host.get_sample_rate(), host.get_block_size(),
+                host.get_tempo(), host.get_vendor_string()));
+        }
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct PairModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "a")]
+        a: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "b")]
+        b: f32
+    }
+}
+
+// an editor that records every parameter notification it gets.
+#[derive(Default)]
+struct UIRecorder;
+
+static UI_VALUES: Mutex<Vec<(&str, f32)>> = Mutex::new(Vec::new());
+
+fixture! {
+    UIRecorder("ui recorder", PairModel, 2 => 2) {
+        fn process(&mut self, _model: &PairModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+impl PluginUI for UIRecorder {
+    type Handle = ();
+
+    fn ui_size() -> (i16, i16) {
+        (100, 100)
+    }
+
+    fn ui_open(_parent: &impl HasRawWindowHandle) -> WindowOpenResult<()> {
+        Ok(())
+    }
+
+    fn ui_close(_handle: ()) {
+    }
+
+    fn ui_param_notify(_handle: &(),
+        param: &'static Param<Self, <PairModel as Model<Self>>::Smooth>, val: f32)
+    {
+        UI_VALUES.lock().unwrap().push((param.get_name(), val));
+    }
+}
+
+// an editor that counts its idle calls, and the parameter notifications it gets.
+#[derive(Default)]
+struct IdleCounter;
+
+static UI_IDLES: AtomicUsize = AtomicUsize::new(0);
+static IDLE_NOTIFICATIONS: AtomicUsize = AtomicUsize::new(0);
+
+fixture! {
+    IdleCounter("idle counter", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+impl PluginUI for IdleCounter {
+    type Handle = ();
+
+    fn ui_size() -> (i16, i16) {
+        (100, 100)
+    }
+
+    fn ui_open(_parent: &impl HasRawWindowHandle) -> WindowOpenResult<()> {
+        Ok(())
+    }
+
+    fn ui_close(_handle: ()) {
+    }
+
+    fn ui_param_notify(_handle: &(),
+        _param: &'static Param<Self, <GainModel as Model<Self>>::Smooth>, _val: f32)
+    {
+        IDLE_NOTIFICATIONS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn ui_idle(_handle: &(), _host: &dyn HostContext) {
+        UI_IDLES.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct FilterModel {
+        #[model(min = 20.0, max = 20000.0)]
+        #[parameter(name = "cutoff")]
+        #[unsmoothed]
+        #[serde(rename = "cutoff_hz")]
+        cutoff: f32
+    }
+}
+
+impl Default for FilterModel {
+    fn default() -> Self {
+        Self {
+            cutoff: 1000.0
+        }
+    }
+}
+
+// keeps its cutoff clear of nyquist, whatever a loaded preset says. outputs the cutoff.
+#[derive(Default)]
+struct Filter;
+
+fixture! {
+    Filter("filter", FilterModel, 2 => 2) {
+        fn validate_model(model: &mut FilterModel, sample_rate: f32) {
+            model.cutoff = model.cutoff.clamp(20.0, sample_rate * 0.4);
+        }
+
+        fn process(&mut self, model: &FilterModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = *model.cutoff;
+                output[1][i] = *model.cutoff;
+            }
+        }
+    }
+}
+
+// records its hooks being called, with the number of frames each was given. takes midi only so
+// that a test can split a buffer into blocks.
+#[derive(Default)]
+struct HookRecorder;
+
+static HOOK_CALLS: Mutex<Vec<(&str, usize)>> = Mutex::new(Vec::new());
+
+fixture! {
+    HookRecorder("hook recorder", GainModel, 2 => 2) {
+        fn pre_process(&mut self, nframes: usize, _musical_time: &MusicalTime) {
+            HOOK_CALLS.lock().unwrap().push(("pre", nframes));
+        }
+
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            HOOK_CALLS.lock().unwrap().push(("process", ctx.nframes));
+        }
+
+        fn post_process(&mut self) {
+            HOOK_CALLS.lock().unwrap().push(("post", 0));
+        }
+    }
+}
+
+impl MidiReceiver for HookRecorder {
+    fn midi_input(&mut self, _model: &GainModelProcess, _data: [u8; 3]) {
+    }
+}
+
+// sends a burst of events from its first block, a MIDI clock tick among them, and nothing after.
+#[derive(Default)]
+struct MidiSender {
+    sent: bool
+}
+
+fixture! {
+    MidiSender("midi sender", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            if self.sent {
+                return;
+            }
+
+            let events = vec![
+                (ctx.nframes - 1, Data::Midi([0x80, 60, 0])),
+                (1, Data::Clock),
+                (0, Data::Midi([0x90, 60, 100]))
+            ];
+
+            for (frame, data) in events {
+                // not to be confused with VST2's `Event`.
+                (ctx.enqueue_event)(baseplug::Event {
+                    frame,
+                    data
+                });
+            }
+
+            self.sent = true;
+        }
+    }
+}
+
+static TRANSPORT_HOOKS: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+// records which of its lifecycle hooks the host has driven, in order.
+struct TransportRecorder;
+
+fixture! {
+    TransportRecorder("transport recorder", GainModel, 2 => 2) {
+        fn new(_sample_rate: f32, _model: &GainModel) -> Self {
+            TRANSPORT_HOOKS.lock().unwrap().push("new");
+            Self
+        }
+
+        fn start_processing(&mut self) {
+            TRANSPORT_HOOKS.lock().unwrap().push("start");
+        }
+
+        fn stop_processing(&mut self) {
+            TRANSPORT_HOOKS.lock().unwrap().push("stop");
+        }
+
+        fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Deserialize)]
+    struct FragileModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "level")]
+        #[unsmoothed]
+        level: f32
+    }
+}
+
+impl Default for FragileModel {
+    fn default() -> Self {
+        Self {
+            level: 0.25
+        }
+    }
+}
+
+// fails to serialise anything over half way, the way a model with some unserialisable state
+// might.
+impl Serialize for FragileModel {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+
+        if self.level > 0.5 {
+            return Err(S::Error::custom("too loud"));
+        }
+
+        let mut state = s.serialize_struct("FragileModel", 1)?;
+        state.serialize_field("level", &self.level)?;
+        state.end()
+    }
+}
+
+// outputs its level, and can't always save it.
+#[derive(Default)]
+struct Fragile;
+
+fixture! {
+    Fragile("fragile", FragileModel, 2 => 2) {
+        fn process(&mut self, model: &FragileModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = *model.level;
+                output[1][i] = *model.level;
+            }
+        }
+    }
+}
+
+static TEARDOWN: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+// records when its editor is closed and when it is dropped itself.
+#[derive(Default)]
+struct TeardownRecorder;
+
+fixture! {
+    TeardownRecorder("teardown recorder", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+impl Drop for TeardownRecorder {
+    fn drop(&mut self) {
+        TEARDOWN.lock().unwrap().push("plugin");
+    }
+}
+
+impl PluginUI for TeardownRecorder {
+    type Handle = ();
+
+    fn ui_size() -> (i16, i16) {
+        (100, 100)
+    }
+
+    fn ui_open(_parent: &impl HasRawWindowHandle) -> WindowOpenResult<()> {
+        Ok(())
+    }
+
+    fn ui_close(_handle: ()) {
+        TEARDOWN.lock().unwrap().push("ui");
+    }
+
+    fn ui_param_notify(_handle: &(),
+        _param: &'static Param<Self, <GainModel as Model<Self>>::Smooth>, _val: f32)
+    {
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct OversamplerModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "drive")]
+        drive: f32,
+
+        // changing it reallocates, so it's not something to automate.
+        #[model(min = 1.0, max = 8.0)]
+        #[parameter(name = "factor", no_automation)]
+        #[unsmoothed]
+        factor: f32
+    }
+}
+
+// has a parameter the host mustn't automate, and otherwise does nothing.
+#[derive(Default)]
+struct Oversampler;
+
+fixture! {
+    Oversampler("oversampler", OversamplerModel, 2 => 2) {
+        fn process(&mut self, _model: &OversamplerModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct TableModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "table", dsp_notify = "count_rebuild", notify_threshold = "0.1")]
+        #[unsmoothed]
+        table: f32
+    }
+}
+
+static REBUILDS: AtomicUsize = AtomicUsize::new(0);
+
+// models are generic over the plugin, so the notify function has to be too.
+fn count_rebuild<P>(_plug: &mut P) {
+    REBUILDS.fetch_add(1, Ordering::SeqCst);
+}
+
+// writes how often its table has been rebuilt so far to its first channel, and the table parameter
+// to its second.
+#[derive(Default)]
+struct TableRebuilder;
+
+fixture! {
+    TableRebuilder("table rebuilder", TableModel, 2 => 2) {
+        fn process(&mut self, model: &TableModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = REBUILDS.load(Ordering::SeqCst) as f32;
+                output[1][i] = *model.table;
+            }
+        }
+    }
+}
+
+// an editor showing the host's tempo, which it asks for while idling.
+#[derive(Default)]
+struct TempoDisplay;
+
+static DISPLAYED_TEMPO: Mutex<Option<f64>> = Mutex::new(None);
+
+fixture! {
+    TempoDisplay("tempo display", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+impl PluginUI for TempoDisplay {
+    type Handle = ();
+
+    fn ui_size() -> (i16, i16) {
+        (100, 100)
+    }
+
+    fn ui_open(_parent: &impl HasRawWindowHandle) -> WindowOpenResult<()> {
+        Ok(())
+    }
+
+    fn ui_close(_handle: ()) {
+    }
+
+    fn ui_param_notify(_handle: &(),
+        _param: &'static Param<Self, <GainModel as Model<Self>>::Smooth>, _val: f32)
+    {
+    }
+
+    fn ui_idle(_handle: &(), host: &dyn HostContext) {
+        *DISPLAYED_TEMPO.lock().unwrap() = host.get_tempo();
+    }
+}
+
+// outputs how many times it's been told to silence everything.
+#[derive(Default)]
+struct PanicCounter {
+    all_notes_off: usize
+}
+
+fixture! {
+    PanicCounter("panic counter", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = self.all_notes_off as f32;
+                output[1][i] = self.all_notes_off as f32;
+            }
+        }
+    }
+}
+
+impl MidiReceiver for PanicCounter {
+    fn midi_input(&mut self, _model: &GainModelProcess, _data: [u8; 3]) {
+    }
+
+    fn all_notes_off(&mut self) {
+        self.all_notes_off += 1;
+    }
+}
+
+// writes a 1.0 on the frame each MIDI message arrived at, silence everywhere else.
+#[derive(Default)]
+struct MidiProbe {
+    pending: bool
+}
+
+fixture! {
+    MidiProbe("midi probe", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, ctx: &mut ProcessContext<Self>) {
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = 0.0;
+                output[1][i] = 0.0;
+            }
+
+            if self.pending {
+                output[0][0] = 1.0;
+                self.pending = false;
+            }
+        }
+    }
+}
+
+impl MidiReceiver for MidiProbe {
+    fn midi_input(&mut self, _model: &GainModelProcess, _data: [u8; 3]) {
+        self.pending = true;
+    }
+}
+
+// an editor that counts the parameter notifications it gets, per parameter.
+#[derive(Default)]
+struct NotifyCounter;
+
+static UI_NOTIFICATIONS: [AtomicUsize; 2] = [AtomicUsize::new(0), AtomicUsize::new(0)];
+
+fixture! {
+    NotifyCounter("notify counter", PairModel, 2 => 2) {
+        fn process(&mut self, _model: &PairModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+impl PluginUI for NotifyCounter {
+    type Handle = ();
+
+    fn ui_size() -> (i16, i16) {
+        (100, 100)
+    }
+
+    fn ui_open(_parent: &impl HasRawWindowHandle) -> WindowOpenResult<()> {
+        Ok(())
+    }
+
+    fn ui_close(_handle: ()) {
+    }
+
+    fn ui_param_notify(_handle: &(),
+        param: &'static Param<Self, <PairModel as Model<Self>>::Smooth>, _val: f32)
+    {
+        let index = if param.get_name() == "a" { 0 } else { 1 };
+        UI_NOTIFICATIONS[index].fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// names the notes of a general MIDI kick and snare, and nothing else.
+#[derive(Default)]
+struct DrumKit;
+
+fixture! {
+    DrumKit("drum kit", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+impl MidiReceiver for DrumKit {
+    fn midi_input(&mut self, _model: &GainModelProcess, _data: [u8; 3]) {
+    }
+
+    fn midi_key_name(&self, note: u8) -> Option<&str> {
+        match note {
+            36 => Some("kick"),
+            38 => Some("snare"),
+            _ => None
+        }
+    }
+}
+
+// an editor that only keeps track of the host info it was told about.
+#[derive(Default)]
+struct UIProbe;
+
+static UI_SAMPLE_RATE: AtomicU32 = AtomicU32::new(0);
+static UI_BLOCK_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+fixture! {
+    UIProbe("ui probe", EmptyModel, 2 => 2) {
+        fn process(&mut self, _model: &EmptyModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+    }
+}
+
+impl PluginUI for UIProbe {
+    type Handle = ();
+
+    fn ui_size() -> (i16, i16) {
+        (100, 100)
+    }
+
+    fn ui_open(_parent: &impl HasRawWindowHandle) -> WindowOpenResult<()> {
+        Ok(())
+    }
+
+    fn ui_close(_handle: ()) {
+    }
+
+    fn ui_param_notify(_handle: &(),
+        _param: &'static Param<Self, <EmptyModel as Model<Self>>::Smooth>, _val: f32)
+    {
+    }
+
+    fn ui_host_info(_handle: &(), sample_rate: f32, max_block_size: Option<usize>) {
+        UI_SAMPLE_RATE.store(sample_rate.to_bits(), Ordering::SeqCst);
+        UI_BLOCK_SIZE.store(max_block_size.unwrap_or(0), Ordering::SeqCst);
+    }
+}
+
+// a name that exactly fills VST2's 32-byte effect name buffer, leaving no room for the nul.
+struct LongName;
+
+impl Plugin for LongName {
+    const NAME: &'static str = "a name of exactly thirty-two b..";
+    const PRODUCT: &'static str = "long name";
+    const VENDOR: &'static str = "baseplug tests";
+
+    const INPUT_CHANNELS: usize = 2;
+    const OUTPUT_CHANNELS: usize = 2;
+
+    type Model = EmptyModel;
+
+    fn new(_sample_rate: f32, _model: &EmptyModel) -> Self {
+        Self
+    }
+
+    fn process(&mut self, _model: &EmptyModelProcess, _ctx: &mut ProcessContext<Self>) {
+    }
+}
+
+// passes its input through, but panics on anything louder than +40dB.
+#[derive(Default)]
+struct Panicky;
+
+fixture! {
+    Panicky("panicky", EmptyModel, 2 => 2) {
+        fn process(&mut self, _model: &EmptyModelProcess, ctx: &mut ProcessContext<Self>) {
+            let input = &ctx.inputs[0].buffers;
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                if input[0][i].abs() > 100.0 {
+                    panic!("input too loud");
+                }
+
+                output[0][i] = input[0][i];
+                output[1][i] = input[1][i];
+            }
+        }
+    }
+}
+
+static PANICKY_NOTES: AtomicUsize = AtomicUsize::new(0);
+
+impl MidiReceiver for Panicky {
+    fn midi_input(&mut self, _model: &EmptyModelProcess, _data: [u8; 3]) {
+        PANICKY_NOTES.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct NoParamsModel {
+    }
+}
+
+// a utility without any parameters.
+#[derive(Default)]
+struct NoParams;
+
+fixture! {
+    NoParams("no params", NoParamsModel, 2 => 2) {
+        fn process(&mut self, _model: &NoParamsModelProcess, ctx: &mut ProcessContext<Self>) {
+            let input = &ctx.inputs[0].buffers;
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = input[0][i];
+                output[1][i] = input[1][i];
+            }
+        }
+    }
+}
+
+// records every parameter change it's told about.
+#[derive(Default)]
+struct ParamRecorder;
+
+static PARAM_SAMPLES: Mutex<Vec<(usize, f32, usize)>> = Mutex::new(Vec::new());
+
+fixture! {
+    ParamRecorder("param recorder", GainModel, 2 => 2) {
+        fn process(&mut self, _model: &GainModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+
+        fn on_param_sample(&mut self, index: usize, normalised: f32, frame: usize) {
+            PARAM_SAMPLES.lock().unwrap().push((index, normalised, frame));
+        }
+    }
+}
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct TriggerModel {
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "level")]
+        level: f32,
+
+        #[model(min = 0.0, max = 1.0)]
+        #[parameter(name = "trigger", momentary, switch)]
+        #[unsmoothed]
+        trigger: f32
+    }
+}
+
+// captures every applied change to its parameters, one of which is momentary.
+#[derive(Default)]
+struct TriggerRecorder;
+
+static TRIGGER_CHANGES: Mutex<Vec<(usize, f32, usize)>> = Mutex::new(Vec::new());
+
+fixture! {
+    TriggerRecorder("trigger recorder", TriggerModel, 2 => 2) {
+        fn process(&mut self, _model: &TriggerModelProcess, _ctx: &mut ProcessContext<Self>) {
+        }
+
+        fn on_parameter_changed(&mut self, index: usize, normalised: f32, frame: usize) {
+            TRIGGER_CHANGES.lock().unwrap().push((index, normalised, frame));
+        }
+    }
+}
+
+// plays each block backwards, reading input from the far end after writing the near end of the
+// output. processing in place without a private copy of the input would hear its own output.
+#[derive(Default)]
+struct Reverser;
+
+fixture! {
+    Reverser("reverser", NoParamsModel, 2 => 2) {
+        fn process(&mut self, _model: &NoParamsModelProcess, ctx: &mut ProcessContext<Self>) {
+            let input = &ctx.inputs[0].buffers;
+            let output = &mut ctx.outputs[0].buffers;
+            let last = ctx.nframes - 1;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = input[0][last - i];
+                output[1][i] = input[1][last - i];
+            }
+        }
+    }
+}
+
+////
+// tests
+////
+
+#[test]
+fn effect_metadata() {
+    let host = MockHost::new::<Gain>();
+    let effect = host.effect();
+
+    assert_eq!(effect.magic, MAGIC);
+    assert_eq!(effect.num_params, 1);
+    assert_eq!(effect.num_inputs, 2);
+    assert_eq!(effect.num_outputs, 2);
+    assert_eq!(effect.unique_id, i32::from_be_bytes(*b"test"));
+    assert_eq!(effect.flags & effect_flags::IS_SYNTH, 0);
+
+    assert_eq!(host.get_string(effect_opcodes::GET_EFFECT_NAME, 0), "gain");
+    assert_eq!(host.get_string(effect_opcodes::GET_VENDOR_STRING, 0), "baseplug tests");
+
+    let synth = MockHost::new::<MidiProbe>();
+    assert_ne!(synth.effect().flags & effect_flags::IS_SYNTH, 0);
+}
+
+#[test]
+fn parameter_strings() {
+    let host = MockHost::new::<Gain>();
+
+    assert_eq!(host.get_string(effect_opcodes::GET_PARAM_NAME, 0), "gain");
+    assert_eq!(host.get_string(effect_opcodes::GET_PARAM_LABEL, 0), "dB");
+    assert_eq!(host.get_string(effect_opcodes::GET_PARAM_DISPLAY, 0), "0.0");
+
+    // out of range indices are refused rather than read out of bounds.
+    assert_eq!(host.dispatch(effect_opcodes::GET_PARAM_NAME, 1, 0,
+        [0u8; 64].as_mut_ptr() as *mut c_void, 0.0), 0);
+}
+
+#[test]
+fn strings_are_truncated_within_their_buffer() {
+    let host = MockHost::new::<LongName>();
+    assert_eq!(LongName::NAME.len(), 32);
+
+    let mut buf = [0xAAu8; 64];
+    host.dispatch(effect_opcodes::GET_EFFECT_NAME, 0, 0, buf.as_mut_ptr() as *mut c_void, 0.0);
+
+    assert_eq!(&buf[..31], &LongName::NAME.as_bytes()[..31]);
+    assert_eq!(buf[31], 0);
+    assert!(buf[32..].iter().all(|&b| b == 0xAA));
+}
+
+#[test]
+fn parameter_properties() {
+    let host = MockHost::new::<Gain>();
+    let mut props: ParameterProperties = unsafe { std::mem::zeroed() };
+
+    let ret = host.dispatch(effect_opcodes::GET_PARAMETER_PROPERTIES, 0, 0,
+        &mut props as *mut ParameterProperties as *mut c_void, 0.0);
+
+    assert_eq!(ret, 1);
+    assert_eq!(props.flags & parameter_flags::IS_SWITCH, 0);
+
+    let label = unsafe { CStr::from_ptr(props.label.as_ptr()) };
+    assert_eq!(label.to_str().unwrap(), "gain");
+
+    // nowhere to put them.
+    let ret = host.dispatch(effect_opcodes::GET_PARAMETER_PROPERTIES, 0, 0,
+        ptr::null_mut(), 0.0);
+    assert_eq!(ret, 0);
+}
+
+#[test]
+fn parameter_round_trip() {
+    let host = MockHost::new::<Gain>();
+
+    host.set_parameter(0, 0.25);
+    assert!((host.get_parameter(0) - 0.25).abs() < 1e-5);
+
+    host.set_parameter(0, 0.0);
+    assert_eq!(host.get_string(effect_opcodes::GET_PARAM_DISPLAY, 0), "-inf");
+}
+
+#[test]
+fn process_applies_gain() {
+    let host = MockHost::new::<Gain>();
+
+    let input = [1.0f32; 64];
+    let mut left = [0.0f32; 64];
+    let mut right = [0.0f32; 64];
+
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert!(left.iter().chain(right.iter()).all(|&x| (x - 1.0).abs() < 1e-5));
+
+    // fully down is -90dB, which is silence. give the smoother plenty of time to get there.
+    host.set_parameter(0, 0.0);
+
+    for _ in 0..100 {
+        host.process([&input, &input], [&mut left, &mut right]);
+    }
+
+    assert!(left.iter().chain(right.iter()).all(|&x| x.abs() < 1e-3));
+}
+
+#[test]
+fn missing_channels_are_tolerated() {
+    let host = MockHost::new::<Gain>();
+
+    let input = [1.0f32; 64];
+    let mut left = [0.0f32; 64];
+
+    let inputs = [input.as_ptr(), ptr::null()];
+    let mut outputs = [left.as_mut_ptr(), ptr::null_mut()];
+    host.process_raw(inputs.as_ptr(), outputs.as_mut_ptr(), 64);
+
+    assert!(left.iter().all(|&x| (x - 1.0).abs() < 1e-5));
+
+    // no inputs at all, silence goes in.
+    host.process_raw(ptr::null(), outputs.as_mut_ptr(), 64);
+    assert!(left.iter().all(|&x| x == 0.0));
+}
+
+#[test]
+fn chunk_round_trip() {
+    let host = MockHost::new::<Gain>();
+
+    host.set_parameter(0, 0.5);
+    let chunk = host.get_chunk();
+
+    host.set_parameter(0, 0.9);
+    host.set_chunk(&chunk);
+
+    assert!((host.get_parameter(0) - 0.5).abs() < 1e-4);
+
+    // the state carries over into a fresh instance too.
+    let other = MockHost::new::<Gain>();
+    other.set_chunk(&chunk);
+    assert!((other.get_parameter(0) - 0.5).abs() < 1e-4);
+}
+
+#[test]
+fn transport_flags_are_respected() {
+    let host = MockHost::new::<TransportProbe>();
+
+    let input = [0.0f32; 16];
+    let mut left = [0.0f32; 16];
+    let mut right = [0.0f32; 16];
+
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    // tempo is valid, the position isn't and the transport is stopped.
+    assert_eq!(left[0], 123.0);
+    assert_eq!(right[0], 0.0);
+}
+
+#[test]
+fn latency_change_is_reported() {
+    let host = MockHost::new::<LatencyProbe>();
+    assert_eq!(host.effect().initial_delay, 0);
+
+    let input = [0.0f32; 16];
+    let mut left = [0.0f32; 16];
+    let mut right = [0.0f32; 16];
+
+    // not from the audio thread though.
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert_eq!(host.effect().initial_delay, 0);
+    assert_eq!(IO_CHANGED_CALLS.load(Ordering::SeqCst), 0);
+
+    host.dispatch(effect_opcodes::EDIT_IDLE, 0, 0, ptr::null_mut(), 0.0);
+    assert_eq!(host.effect().initial_delay, 64);
+    assert_eq!(IO_CHANGED_CALLS.load(Ordering::SeqCst), 1);
+
+    // unchanged latency doesn't bother the host again.
+    host.process([&input, &input], [&mut left, &mut right]);
+    host.dispatch(effect_opcodes::EDIT_IDLE, 0, 0, ptr::null_mut(), 0.0);
+    assert_eq!(IO_CHANGED_CALLS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn midi_events_split_the_block() {
+    let host = MockHost::new::<MidiProbe>();
+
+    let input = [0.0f32; 64];
+    let mut left = [0.0f32; 64];
+    let mut right = [0.0f32; 64];
+
+    host.send_midi(16, [0x90, 60, 100]);
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    let hits: Vec<_> = (0..64).filter(|&i| left[i] == 1.0).collect();
+    assert_eq!(hits, [16]);
+
+    // events beyond the end of the block land on its last frame instead of getting lost.
+    host.send_midi(1000, [0x90, 60, 100]);
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    let hits: Vec<_> = (0..64).filter(|&i| left[i] == 1.0).collect();
+    assert_eq!(hits, [63]);
+}
+
+#[test]
+fn editor_idles_without_audio() {
+    let host = MockHost::new::<IdleCounter>();
+    assert_eq!(host.dispatch(effect_opcodes::EDIT_OPEN, 0, 0, ptr::null_mut(), 0.0), 1);
+
+    for _ in 0..3 {
+        host.dispatch(effect_opcodes::EDIT_IDLE, 0, 0, ptr::null_mut(), 0.0);
+    }
+
+    assert_eq!(UI_IDLES.load(Ordering::SeqCst), 3);
+
+    // a state load still reaches the editor, even though no block ever runs.
+    let chunk = host.get_chunk();
+    host.set_chunk(&chunk);
+    host.dispatch(effect_opcodes::EDIT_IDLE, 0, 0, ptr::null_mut(), 0.0);
+
+    assert_eq!(UI_IDLES.load(Ordering::SeqCst), 4);
+    assert_eq!(IDLE_NOTIFICATIONS.load(Ordering::SeqCst), 1);
+
+    host.dispatch(effect_opcodes::EDIT_CLOSE, 0, 0, ptr::null_mut(), 0.0);
+}
+
+#[test]
+fn midi_sync_is_decoded() {
+    let host = MockHost::new::<SyncDecoder>();
+
+    let input = [0.0f32; 32];
+    let mut left = [0.0f32; 32];
+    let mut right = [0.0f32; 32];
+
+    host.send_midi(0, [0xF8, 0, 0]);
+
+    // LSB first, seven bits each: (3 << 7) | 5.
+    host.send_midi(10, [0xF2, 0x05, 0x03]);
+
+    host.send_midi(20, [0x90, 60, 100]);
+
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    for (i, &sample) in left.iter().enumerate() {
+        let expected = match i {
+            0 => -1.0,
+            10 => 10389.0,
+            20 => 144.0,
+            _ => 0.0
+        };
+
+        assert_eq!(sample, expected, "frame {}", i);
+    }
+}
+
+#[test]
+fn host_can_be_queried() {
+    let host = MockHost::new::<HostQuerier>();
+
+    let input = [0.0f32; 16];
+    let mut left = [0.0f32; 16];
+    let mut right = [0.0f32; 16];
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    assert_eq!(*HOST_ANSWERS.lock().unwrap(),
+        Some((Some(48000.0), Some(256), Some(123.0), Some("mock vendor".to_string()))));
+}
+
+#[test]
+fn editor_sees_loaded_state() {
+    let input = [0.0f32; 16];
+    let mut left = [0.0f32; 16];
+    let mut right = [0.0f32; 16];
+
+    let source = MockHost::new::<UIRecorder>();
+    source.set_parameter(0, 0.25);
+    source.set_parameter(1, 0.75);
+    source.process([&input, &input], [&mut left, &mut right]);
+    let chunk = source.get_chunk();
+
+    let host = MockHost::new::<UIRecorder>();
+    assert_eq!(host.dispatch(effect_opcodes::EDIT_OPEN, 0, 0, ptr::null_mut(), 0.0), 1);
+
+    // straight away, without waiting for a block or an idle.
+    host.set_chunk(&chunk);
+    assert_eq!(*UI_VALUES.lock().unwrap(), vec![("a", 0.25), ("b", 0.75)]);
+
+    host.dispatch(effect_opcodes::EDIT_CLOSE, 0, 0, ptr::null_mut(), 0.0);
+}
+
+#[test]
+fn loaded_state_is_validated() {
+    let host = MockHost::new::<Filter>();
+
+    let input = [0.0f32; 16];
+    let mut left = [0.0f32; 16];
+    let mut right = [0.0f32; 16];
+
+    // way past the parameter's range, and past nyquist at 48kHz.
+    host.set_chunk(br#"{"cutoff_hz":50000.0}"#);
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    assert!(left.iter().all(|&x| (x - 19200.0).abs() < 0.1));
+}
+
+#[test]
+fn buffer_hooks_run_once_per_buffer() {
+    let host = MockHost::new::<HookRecorder>();
+
+    let input = [0.0f32; 32];
+    let mut left = [0.0f32; 32];
+    let mut right = [0.0f32; 32];
+
+    // splits the buffer into three blocks.
+    host.send_midi(10, [0x90, 60, 100]);
+    host.send_midi(20, [0x80, 60, 0]);
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    host.process([&input[..8], &input[..8]], [&mut left[..8], &mut right[..8]]);
+
+    assert_eq!(*HOOK_CALLS.lock().unwrap(), vec![
+        ("pre", 32), ("process", 10), ("process", 10), ("process", 12), ("post", 0),
+        ("pre", 8), ("process", 8), ("post", 0)
+    ]);
+}
+
+#[test]
+fn midi_output_reaches_the_host() {
+    let host = MockHost::new::<MidiSender>();
+
+    let input = [0.0f32; 16];
+    let mut left = [0.0f32; 16];
+    let mut right = [0.0f32; 16];
+
+    // the clock tick isn't MIDI as far as VST2 output is concerned, and mustn't leave a gap.
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert_eq!(*SENT_MIDI.lock().unwrap(), vec![(0, [0x90, 60, 100]), (15, [0x80, 60, 0])]);
+
+    // sent events are gone from the queue.
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert_eq!(SENT_MIDI.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn start_and_stop_process_reach_the_plugin() {
+    let host = MockHost::new::<TransportRecorder>();
+    TRANSPORT_HOOKS.lock().unwrap().clear();
+
+    host.dispatch(effect_opcodes::START_PROCESS, 0, 0, ptr::null_mut(), 0.0);
+    host.dispatch(effect_opcodes::STOP_PROCESS, 0, 0, ptr::null_mut(), 0.0);
+    host.dispatch(effect_opcodes::START_PROCESS, 0, 0, ptr::null_mut(), 0.0);
+
+    // and without rebuilding the plugin, unlike a reset.
+    assert_eq!(*TRANSPORT_HOOKS.lock().unwrap(), vec!["start", "stop", "start"]);
+}
+
+
+#[test]
+fn no_automation_params_arent_automatable() {
+    let host = MockHost::new::<Oversampler>();
+
+    assert_eq!(host.dispatch(effect_opcodes::CAN_BE_AUTOMATED, 0, 0, ptr::null_mut(), 0.0), 1);
+    assert_eq!(host.dispatch(effect_opcodes::CAN_BE_AUTOMATED, 1, 0, ptr::null_mut(), 0.0), 0);
+
+    // but it's still there for the user to see and set.
+    assert_eq!(host.get_string(effect_opcodes::GET_PARAM_NAME, 1), "factor");
+    host.set_parameter(1, 1.0);
+    assert_eq!(host.get_parameter(1), 1.0);
+}
+
+#[test]
+fn open_editor_is_closed_before_the_plugin_is_dropped() {
+    let host = MockHost::new::<TeardownRecorder>();
+    assert_eq!(host.dispatch(effect_opcodes::EDIT_OPEN, 0, 0, ptr::null_mut(), 0.0), 1);
+
+    // no EDIT_CLOSE first, straight to CLOSE. the effect is gone afterwards, so `host` mustn't
+    // dispatch anything more on drop.
+    TEARDOWN.lock().unwrap().clear();
+    host.dispatch(effect_opcodes::CLOSE, 0, 0, ptr::null_mut(), 0.0);
+    std::mem::forget(host);
+
+    assert_eq!(*TEARDOWN.lock().unwrap(), vec!["ui", "plugin"]);
+}
+
+#[test]
+fn small_changes_skip_dsp_notify() {
+    let host = MockHost::new::<TableRebuilder>();
+
+    let input = [0.0f32; 16];
+    let mut left = [0.0f32; 16];
+    let mut right = [0.0f32; 16];
+
+    host.set_parameter(0, 0.05);
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert_eq!(left, [0.0; 16]);
+
+    // measured from the last notified value, not the last set one.
+    host.set_parameter(0, 0.12);
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert_eq!(left, [1.0; 16]);
+
+    // the value changes either way, only the notification is skipped.
+    host.set_parameter(0, 0.2);
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert_eq!(left, [1.0; 16]);
+    assert_eq!(right, [0.2; 16]);
+}
+
+
+#[test]
+fn editor_reads_tempo_without_audio() {
+    let host = MockHost::new::<TempoDisplay>();
+    assert_eq!(host.dispatch(effect_opcodes::EDIT_OPEN, 0, 0, ptr::null_mut(), 0.0), 1);
+
+    // the transport is stopped and nothing has been processed, but the tempo is valid.
+    host.dispatch(effect_opcodes::EDIT_IDLE, 0, 0, ptr::null_mut(), 0.0);
+    assert_eq!(*DISPLAYED_TEMPO.lock().unwrap(), Some(123.0));
+
+    host.dispatch(effect_opcodes::EDIT_CLOSE, 0, 0, ptr::null_mut(), 0.0);
+}
+
+#[test]
+fn all_notes_off_on_cc_120_and_123() {
+    let host = MockHost::new::<PanicCounter>();
+
+    host.send_midi(10, [0xB0, 123, 0]);
+    host.send_midi(20, [0xB3, 120, 0]);
+
+    // any other controller is left alone.
+    host.send_midi(30, [0xB0, 7, 0]);
+
+    let input = [0.0f32; 40];
+    let mut left = [0.0f32; 40];
+    let mut right = [0.0f32; 40];
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    for (i, &sample) in left.iter().enumerate() {
+        let expected = match i {
+            0..=9 => 0.0,
+            10..=19 => 1.0,
+            _ => 2.0
+        };
+
+        assert_eq!(sample, expected, "frame {}", i);
+    }
+}
+
+
+#[test]
+fn out_of_range_midi_is_kept_in_the_block() {
+    let host = MockHost::new::<MidiProbe>();
+
+    let input = [0.0f32; 64];
+    let mut left = [0.0f32; 64];
+    let mut right = [0.0f32; 64];
+
+    // before the start, in range, and well past the end, all in the same block.
+    host.send_midi(-5, [0x90, 60, 100]);
+    host.send_midi(10, [0x90, 62, 100]);
+    host.send_midi(500, [0x90, 64, 100]);
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    let hits: Vec<_> = (0..64).filter(|&i| left[i] == 1.0).collect();
+    assert_eq!(hits, [0, 10, 63]);
+
+    // and nothing is left over to turn up in the next one.
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert!(left.iter().all(|&x| x == 0.0));
+}
+
+#[test]
+fn failed_saves_store_the_defaults() {
+    let host = MockHost::new::<Fragile>();
+
+    host.set_parameter(0, 0.4);
+    let other = MockHost::new::<Fragile>();
+    other.set_chunk(&host.get_chunk());
+    assert!((other.get_parameter(0) - 0.4).abs() < 1e-6);
+
+    // the host still gets a chunk, and it loads back into a working plugin.
+    host.set_parameter(0, 0.9);
+    let other = MockHost::new::<Fragile>();
+    other.set_parameter(0, 0.4);
+    other.set_chunk(&host.get_chunk());
+    assert_eq!(other.get_parameter(0), 0.25);
+}
+
+
+#[test]
+fn ui_is_notified_once_per_block() {
+    let host = MockHost::new::<NotifyCounter>();
+    let notifications = || [
+        UI_NOTIFICATIONS[0].load(Ordering::SeqCst),
+        UI_NOTIFICATIONS[1].load(Ordering::SeqCst)
+    ];
+
+    assert_eq!(host.dispatch(effect_opcodes::EDIT_OPEN, 0, 0, ptr::null_mut(), 0.0), 1);
+
+    let input = [0.0f32; 16];
+    let mut left = [0.0f32; 16];
+    let mut right = [0.0f32; 16];
+
+    // dense automation of one parameter between two blocks.
+    for i in 1..=10 {
+        host.set_parameter(0, i as f32 / 10.0);
+    }
+
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert_eq!(notifications(), [1, 0]);
+
+    // nothing changed, nothing to tell.
+    host.process([&input, &input], [&mut left, &mut right]);
+    host.dispatch(effect_opcodes::EDIT_IDLE, 0, 0, ptr::null_mut(), 0.0);
+    assert_eq!(notifications(), [1, 0]);
+
+    host.set_parameter(0, 0.5);
+    host.set_parameter(1, 0.5);
+    host.set_parameter(1, 0.25);
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert_eq!(notifications(), [2, 1]);
+
+    host.dispatch(effect_opcodes::EDIT_CLOSE, 0, 0, ptr::null_mut(), 0.0);
+}
+
+
+#[test]
+fn drum_notes_are_named() {
+    let host = MockHost::new::<DrumKit>();
+
+    assert_eq!(host.midi_key_name(36).as_deref(), Some("kick"));
+    assert_eq!(host.midi_key_name(38).as_deref(), Some("snare"));
+    assert_eq!(host.midi_key_name(37), None);
+
+    // and nothing out of MIDI's range.
+    assert_eq!(host.midi_key_name(-1), None);
+    assert_eq!(host.midi_key_name(128), None);
+
+    // plugins that don't take MIDI have no names at all.
+    let host = MockHost::new::<Filter>();
+    assert_eq!(host.midi_key_name(36), None);
+}
+
+#[test]
+fn renamed_fields_round_trip() {
+    let host = MockHost::new::<Filter>();
+
+    host.set_parameter(0, 0.25);
+    let chunk = host.get_chunk();
+
+    let state: serde_json::Value = serde_json::from_slice(&chunk).unwrap();
+    assert!(state.get("cutoff_hz").is_some());
+    assert!(state.get("cutoff").is_none());
+
+    let other = MockHost::new::<Filter>();
+    other.set_chunk(&chunk);
+    assert!((other.get_parameter(0) - 0.25).abs() < 1e-4);
+}
+
+
+#[test]
+fn ui_is_told_about_host_info() {
+    let host = MockHost::new::<UIProbe>();
+    let ui_sample_rate = || f32::from_bits(UI_SAMPLE_RATE.load(Ordering::SeqCst));
+
+    assert_eq!(host.dispatch(effect_opcodes::EDIT_OPEN, 0, 0, ptr::null_mut(), 0.0), 1);
+    assert_eq!(ui_sample_rate(), 48000.0);
+    assert_eq!(UI_BLOCK_SIZE.load(Ordering::SeqCst), 0);
+
+    host.dispatch(effect_opcodes::SET_SAMPLE_RATE, 0, 0, ptr::null_mut(), 96000.0);
+    assert_eq!(ui_sample_rate(), 96000.0);
+
+    host.dispatch(effect_opcodes::SET_BLOCK_SIZE, 0, 512, ptr::null_mut(), 0.0);
+    assert_eq!(UI_BLOCK_SIZE.load(Ordering::SeqCst), 512);
+
+    host.dispatch(effect_opcodes::EDIT_CLOSE, 0, 0, ptr::null_mut(), 0.0);
+}
+
+#[test]
+fn plugin_without_parameters() {
+    let host = MockHost::new::<NoParams>();
+    assert_eq!(host.effect().num_params, 0);
+
+    assert_eq!(host.dispatch(effect_opcodes::GET_PARAM_NAME, 0, 0,
+        [0u8; 64].as_mut_ptr() as *mut c_void, 0.0), 0);
+
+    host.set_parameter(0, 1.0);
+    assert_eq!(host.get_parameter(0), 0.0);
+
+    let chunk = host.get_chunk();
+    host.set_chunk(&chunk);
+
+    let input = [0.5f32; 32];
+    let mut left = [0.0f32; 32];
+    let mut right = [0.0f32; 32];
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    assert_eq!(left, input);
+    assert_eq!(right, input);
+}
+
+#[test]
+fn panic_in_process_silences_the_block() {
+    let host = MockHost::new::<Panicky>();
+
+    let loud = [1000.0f32; 32];
+    let mut left = [1.0f32; 32];
+    let mut right = [1.0f32; 32];
+
+    // the panic stays on our side of the ABI, and the host gets silence for this block.
+    host.process([&loud, &loud], [&mut left, &mut right]);
+    assert_eq!(left, [0.0; 32]);
+    assert_eq!(right, [0.0; 32]);
+
+    // the instance is still usable afterwards.
+    let input = [0.5f32; 32];
+    host.process([&input, &input], [&mut left, &mut right]);
+    assert_eq!(left, input);
+    assert_eq!(right, input);
+
+    host.set_parameter(0, 0.25);
+    assert!((host.get_parameter(0) - 0.25).abs() < 1e-5);
+}
+
+
+#[test]
+fn looking_at_pending_sets_doesnt_apply_them() {
+    let host = MockHost::new::<ParamRecorder>();
+
+    host.set_parameter(0, 0.0);
+    assert_eq!(host.get_string(effect_opcodes::GET_PARAM_DISPLAY, 0), "-inf");
+
+    let chunk = host.get_chunk();
+    assert!(PARAM_SAMPLES.lock().unwrap().is_empty());
+
+    // the set is still applied (and reported) at the top of the next block.
+    let input = [0.0f32; 16];
+    let mut left = [0.0f32; 16];
+    let mut right = [0.0f32; 16];
+    host.process([&input, &input], [&mut left, &mut right]);
+
+    assert_eq!(*PARAM_SAMPLES.lock().unwrap(), vec![(0, 0.0, 0)]);
+
+    // and the chunk taken before then has it.
+    let other = MockHost::new::<Gain>();
+    other.set_chunk(&chunk);
+    assert_eq!(other.get_parameter(0), 0.0);
+}
+
+
+#[test]
+fn every_parameter_change_is_reported() {
+    let host = MockHost::new::<TriggerRecorder>();
+
+    let process = || {
+        let input = [0.0f32; 16];
+        let mut left = [0.0f32; 16];
+        let mut right = [0.0f32; 16];
+        host.process([&input, &input], [&mut left, &mut right]);
+    };
+
+    host.set_parameter(0, 0.5);
+    host.set_parameter(1, 1.0);
+    process();
+
+    // the trigger springs back by itself.
+    process();
+
+    let chunk = host.get_chunk();
+    host.set_parameter(0, 0.25);
+    process();
+
+    // a state load reports every parameter.
+    host.set_chunk(&chunk);
+    process();
+
+    // and then it's quiet.
+    process();
+
+    assert_eq!(*TRIGGER_CHANGES.lock().unwrap(), vec![
+        (0, 0.5, 0), (1, 1.0, 0),
+        (1, 0.0, 0),
+        (0, 0.25, 0),
+        (0, 0.5, 0), (1, 0.0, 0)
+    ]);
+}
+
+
+#[test]
+fn in_place_input_is_copied() {
+    let host = MockHost::new::<Reverser>();
+
+    let mut left: Vec<f32> = (0..64).map(|i| i as f32).collect();
+    let mut right: Vec<f32> = (0..64).map(|i| -(i as f32)).collect();
+
+    let inputs = [left.as_ptr(), right.as_ptr()];
+    let mut outputs = [left.as_mut_ptr(), right.as_mut_ptr()];
+    host.process_raw(inputs.as_ptr(), outputs.as_mut_ptr(), 64);
+
+    assert!(left.iter().rev().enumerate().all(|(i, &x)| x == i as f32));
+    assert!(right.iter().rev().enumerate().all(|(i, &x)| x == -(i as f32)));
+}
+
+#[test]
+fn switch_properties() {
+    let host = MockHost::new::<TriggerRecorder>();
+
+    // hosts don't always clear the struct first.
+    let mut props: ParameterProperties = unsafe { std::mem::zeroed() };
+    props.min_integer = -1;
+    props.max_integer = -1;
+
+    let ret = host.dispatch(effect_opcodes::GET_PARAMETER_PROPERTIES, 0, 0,
+        &mut props as *mut ParameterProperties as *mut c_void, 0.0);
+
+    assert_eq!(ret, 1);
+    assert_eq!(props.flags, 0);
+    assert_eq!((props.min_integer, props.max_integer), (0, 0));
+
+    host.dispatch(effect_opcodes::GET_PARAMETER_PROPERTIES, 1, 0,
+        &mut props as *mut ParameterProperties as *mut c_void, 0.0);
+
+    assert_ne!(props.flags & parameter_flags::IS_SWITCH, 0);
+    assert_ne!(props.flags & parameter_flags::USES_INTEGER_MIN_MAX, 0);
+    assert_eq!((props.min_integer, props.max_integer, props.step_integer), (0, 1, 1));
+}
+
+#[test]
+fn panic_in_process_drops_the_blocks_events() {
+    let host = MockHost::new::<Panicky>();
+
+    let loud = [1000.0f32; 32];
+    let quiet = [0.5f32; 32];
+    let mut left = [0.0f32; 32];
+    let mut right = [0.0f32; 32];
+
+    // the panic comes before the note's frame, so the note never gets played.
+    host.send_midi(20, [0x90, 60, 100]);
+    host.process([&loud, &loud], [&mut left, &mut right]);
+
+    // and doesn't turn up late either.
+    host.process([&quiet, &quiet], [&mut left, &mut right]);
+    assert_eq!(PANICKY_NOTES.load(Ordering::SeqCst), 0);
+}
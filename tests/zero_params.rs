@@ -0,0 +1,59 @@
+// a plugin without any parameters.
+
+#[macro_use]
+mod common;
+
+use serde::{Serialize, Deserialize};
+
+use baseplug::{
+    Model,
+    Parameters,
+    ProcessContext,
+    Instance
+};
+
+
+baseplug::model! {
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct NoParamsModel {
+    }
+}
+
+// flips the polarity of its input.
+#[derive(Default)]
+struct PhaseFlip;
+
+fixture! {
+    PhaseFlip("phase flip", NoParamsModel, 2 => 2) {
+        fn process(&mut self, _model: &NoParamsModelProcess, ctx: &mut ProcessContext<Self>) {
+            let input = &ctx.inputs[0].buffers;
+            let output = &mut ctx.outputs[0].buffers;
+
+            for i in 0..ctx.nframes {
+                output[0][i] = -input[0][i];
+                output[1][i] = -input[1][i];
+            }
+        }
+    }
+}
+
+#[test]
+fn plugin_without_parameters() {
+    type Smooth = <NoParamsModel as Model<PhaseFlip>>::Smooth;
+
+    assert!(<Smooth as Parameters<PhaseFlip, _>>::PARAMS.is_empty());
+    assert_eq!(<NoParamsModel as Model<PhaseFlip>>::PARAM_COUNT, 0);
+
+    let mut instance = Instance::<PhaseFlip>::new(48000.0);
+
+    assert_eq!(instance.get_parameter(0), None);
+    instance.set_parameter(0, 1.0);
+    instance.reset_parameter(0);
+    instance.automate(0, &[(0, 1.0)]);
+    assert!(instance.settle(128));
+
+    let output = instance.process_owned(&[vec![0.5; 16], vec![-0.25; 16]], 16);
+
+    assert_eq!(output[0], vec![-0.5; 16]);
+    assert_eq!(output[1], vec![0.25; 16]);
+}